@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use pelcodrs::Message;
+
+// Any parsed message must be displayed back to the same message
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(message) = text.parse::<Message>() {
+            assert_eq!(message, message.to_string().parse::<Message>().unwrap());
+            assert_eq!(
+                message,
+                format!("{:X}", message).parse::<Message>().unwrap()
+            );
+        }
+    }
+});
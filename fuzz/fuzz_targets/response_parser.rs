@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use pelcodrs::{ResponseKind, ResponseParser};
+
+// The first byte selects the kind of responses and the size of the chunks
+fuzz_target!(|data: &[u8]| {
+    if let Some((&selector, bytes)) = data.split_first() {
+        let kind = match selector % 3 {
+            0 => ResponseKind::General,
+            1 => ResponseKind::Extended,
+            _ => ResponseKind::Query,
+        };
+        let chunk_size = usize::from(selector / 3 % 16) + 1;
+
+        let mut parser = ResponseParser::new(kind);
+        for chunk in bytes.chunks(chunk_size) {
+            parser.push(chunk);
+            while let Some(result) = parser.next_response() {
+                if let Ok(response) = result {
+                    assert_eq!(kind, response.kind());
+                }
+            }
+        }
+    }
+});
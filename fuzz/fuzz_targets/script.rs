@@ -0,0 +1,14 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use pelcodrs::{CaptureReader, Script};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(script) = text.parse::<Script>() {
+            let _ = script.dry_run();
+        }
+    }
+    for record in CaptureReader::new(data) {
+        let _ = record;
+    }
+});
@@ -0,0 +1,279 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{Read, Write};
+use std::num::NonZeroU8;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+use crate::text::command_name;
+
+/// Address byte used for the broadcasts.
+pub const BROADCAST_ADDRESS: u8 = 0x00;
+
+/// Address byte used for the broadcasts by some installations instead of
+/// [BROADCAST_ADDRESS](constant.BROADCAST_ADDRESS.html).
+pub const ALTERNATE_BROADCAST_ADDRESS: u8 = 0xFF;
+
+// Extended opcodes which are safe to broadcast, see
+// Message::is_broadcast_safe(). The others, including the unknown ones, are
+// not.
+const BROADCAST_SAFE_OPCODES: &[u8] = &[
+    0x07, // Go to preset, flip and zero pan
+    0x09, // Set auxiliary
+    0x0B, // Clear auxiliary
+    0x19, // Alarm acknowledge
+    0x1B, // Zone scan on
+    0x1D, // Zone scan off
+    0x23, // Run pattern
+    0x25, // Zoom speed
+    0x27, // Focus speed
+    0x2B, // Auto focus
+    0x2D, // Auto iris
+    0x2F, // AGC
+    0x31, // Backlight compensation
+    0x33, // Auto white balance
+    0x35, // Device phase delay mode
+    0x37, // Shutter speed
+    0x39, // Line lock phase delay
+    0x3B, // White balance R-B
+    0x3D, // White balance M-G
+    0x3F, // Gain
+    0x41, // Auto iris level
+    0x43, // Auto iris peak
+];
+
+/// Target of a message: a single unit, or all the devices of the bus.
+///
+/// The units have an address between 1 and 255, checked by the conversion
+/// from `u8`:
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::convert::TryFrom;
+/// assert_eq!("10", Address::try_from(10).unwrap().to_string());
+/// assert!(Address::try_from(0).is_err());
+/// ```
+///
+/// Pelco D does not define a broadcast address, but most receivers accept the
+/// commands sent to address 0 whatever their own address. Installations using
+/// 0xFF as a broadcast address instead can target it as a unit, which is
+/// considered a broadcast by
+/// [Address::is_broadcast()](enum.Address.html#method.is_broadcast), or set
+/// it as the broadcast address of the port with
+/// [PelcoDPort::set_broadcast_address()](struct.PelcoDPort.html#method.set_broadcast_address).
+///
+/// All the devices receiving a broadcast execute it at the same time, and do
+/// not answer it, since their replies would collide on the line. Only the
+/// commands for which [Message::is_broadcast_safe()](struct.Message.html#method.is_broadcast_safe)
+/// returns `true` should be broadcast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Address {
+    Broadcast,
+    Unit(NonZeroU8),
+}
+
+impl Address {
+    /// Address of a unit, or `None` for 0. This is the same as
+    /// `Address::try_from()`, usable in constant expressions.
+    pub const fn unit(address: u8) -> Option<Address> {
+        match NonZeroU8::new(address) {
+            Some(address) => Some(Address::Unit(address)),
+            None => None,
+        }
+    }
+
+    /// Address of the address byte of a frame, 0 being the broadcast.
+    pub(crate) const fn from_byte(byte: u8) -> Address {
+        match Address::unit(byte) {
+            Some(address) => address,
+            None => Address::Broadcast,
+        }
+    }
+
+    /// Address byte in the frames.
+    pub const fn byte(self) -> u8 {
+        match self {
+            Address::Broadcast => BROADCAST_ADDRESS,
+            Address::Unit(address) => address.get(),
+        }
+    }
+
+    /// Whether the address reaches all the devices, which is the case of
+    /// `Address::Broadcast` and of the unit 0xFF.
+    pub const fn is_broadcast(self) -> bool {
+        match self {
+            Address::Broadcast => true,
+            Address::Unit(address) => address.get() == ALTERNATE_BROADCAST_ADDRESS,
+        }
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address.byte()
+    }
+}
+
+impl From<NonZeroU8> for Address {
+    fn from(address: NonZeroU8) -> Self {
+        Address::Unit(address)
+    }
+}
+
+impl TryFrom<u8> for Address {
+    type Error = Error;
+
+    /// Address of a unit. An error is returned for 0, which is not a valid
+    /// unit address: `Address::Broadcast` must be used explicitly instead.
+    fn try_from(address: u8) -> Result<Self> {
+        match Address::unit(address) {
+            Some(address) => Ok(address),
+            None => Err(Error::new(
+                ErrorKind::InvalidValue,
+                "Invalid unit address 0, use Address::Broadcast to broadcast",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Broadcast => write!(f, "broadcast"),
+            Address::Unit(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+/// Conversion to an [Address](enum.Address.html), for the arguments of the
+/// [Message](struct.Message.html) constructors.
+///
+/// It is implemented by `Address`, and by `u8` for compatibility, in which
+/// case 0 is rejected like by `Address::try_from()`.
+pub trait ToAddress {
+    fn to_address(self) -> Result<Address>;
+}
+
+impl ToAddress for Address {
+    fn to_address(self) -> Result<Address> {
+        Ok(self)
+    }
+}
+
+impl ToAddress for NonZeroU8 {
+    fn to_address(self) -> Result<Address> {
+        Ok(Address::Unit(self))
+    }
+}
+
+impl ToAddress for u8 {
+    fn to_address(self) -> Result<Address> {
+        Address::try_from(self)
+    }
+}
+
+impl Message {
+    /// Copy of the message sent to another address, with the checksum
+    /// updated.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// # use std::convert::TryFrom;
+    /// # fn example() -> Result<()> {
+    /// let msg = Message::go_to_preset(10, 1)?;
+    /// let unit = Address::try_from(3)?;
+    /// assert_eq!(Message::go_to_preset(unit, 1)?, msg.with_address(unit));
+    /// # Ok(())}
+    /// # example().unwrap();
+    /// ```
+    pub fn with_address(self, address: Address) -> Message {
+        let words = self.as_ref();
+        Message::from_bytes(address.byte(), [words[2], words[3], words[4], words[5]])
+    }
+
+    /// Whether the command can be broadcast to all the devices.
+    ///
+    /// The safe commands act on the current state of the devices: stop and
+    /// motion, go to preset, run pattern, auxiliary outputs, zone scan, alarm
+    /// acknowledge and the lens and exposure settings.
+    ///
+    /// The others are unsafe:
+    ///
+    ///  * The commands storing something in the devices (set or clear preset,
+    ///    zone limits, pattern recording), which would overwrite the setup of
+    ///    every device with the same data.
+    ///
+    ///  * The resets (remote reset, camera defaults), which would take down
+    ///    the whole installation at once.
+    ///
+    ///  * The on-screen display writes, the pan position and the query, which
+    ///    are specific to a single device, the latter expecting a reply.
+    ///
+    /// The unknown extended commands are considered unsafe.
+    pub fn is_broadcast_safe(&self) -> bool {
+        let opcode = self.as_ref()[3];
+        opcode & 1 == 0 || BROADCAST_SAFE_OPCODES.contains(&opcode)
+    }
+}
+
+impl<T: Read + Write> PelcoDPort<T> {
+    /// Send a message to all the devices with a single frame at the broadcast
+    /// address of the port. An error is returned if the command is not safe
+    /// to broadcast, see
+    /// [Message::is_broadcast_safe()](struct.Message.html#method.is_broadcast_safe).
+    ///
+    /// No response should be expected after a broadcast.
+    pub fn broadcast(&mut self, message: Message) -> Result<()> {
+        check_broadcast(&message)?;
+        self.send_message(message.with_address(self.broadcast_address()))
+    }
+
+    /// Send a message to each of the given addresses in turn, for the
+    /// receivers not accepting broadcasts or to reach only a part of the bus.
+    /// The rate limit of the port applies between the frames.
+    ///
+    /// Returns the number of frames sent. All the addresses are checked
+    /// before sending the first frame: an error is returned without sending
+    /// anything if an address is invalid, or if a broadcast address is given
+    /// with a command not safe to broadcast.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// # fn example() -> Result<()> {
+    /// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+    /// let preset = Message::go_to_preset(1, 1)?;
+    /// assert_eq!(8, port.send_to_all(preset, 1..=8)?);
+    /// # Ok(())}
+    /// # example().unwrap();
+    /// ```
+    pub fn send_to_all<I>(&mut self, message: Message, addresses: I) -> Result<usize>
+    where
+        I: IntoIterator,
+        I::Item: ToAddress,
+    {
+        let addresses = addresses
+            .into_iter()
+            .map(ToAddress::to_address)
+            .collect::<Result<Vec<Address>>>()?;
+        if addresses.iter().any(|address| address.is_broadcast()) {
+            check_broadcast(&message)?;
+        }
+        for address in addresses.iter() {
+            self.send_message(message.with_address(*address))?;
+        }
+        Ok(addresses.len())
+    }
+}
+
+fn check_broadcast(message: &Message) -> Result<()> {
+    if message.is_broadcast_safe() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidValue,
+            &format!("{} cannot be broadcast", command_name(message)),
+        ))
+    }
+}
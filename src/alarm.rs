@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::sync::mpsc::Sender;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+use crate::response::*;
+
+const ALARM_COUNT: u8 = 8;
+
+/// Transition of an alarm input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlarmState {
+    /// The alarm input became active.
+    Raised,
+    /// The alarm input became inactive.
+    Cleared,
+}
+
+/// Change of an alarm input of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlarmEvent {
+    pub address: u8,
+    /// Alarm number, from 1 to 8.
+    pub alarm: u8,
+    pub state: AlarmState,
+}
+
+/// Alarm handling from the general responses of the devices.
+///
+/// The monitor decodes the alarm byte of the general responses, and reports
+/// the raised and cleared alarms. On a raised alarm, it can send configured
+/// actions and acknowledge the alarm.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// let mut monitor = AlarmMonitor::new();
+/// monitor
+///     .set_auto_acknowledge(true)
+///     .set_actions(10, 2, vec![Message::go_to_preset(10, 5)?]);
+///
+/// let response = Response::General { address: 10, alarms: 0b0000_0010 };
+/// let events = monitor.handle(&mut port, &response)?;
+/// assert_eq!(
+///     vec![AlarmEvent { address: 10, alarm: 2, state: AlarmState::Raised }],
+///     events
+/// );
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AlarmMonitor {
+    states: BTreeMap<u8, u8>,
+    actions: BTreeMap<(u8, u8), Vec<Message>>,
+    auto_acknowledge: bool,
+    sender: Option<Sender<AlarmEvent>>,
+}
+
+impl AlarmMonitor {
+    pub fn new() -> AlarmMonitor {
+        AlarmMonitor::default()
+    }
+
+    /// Send `Message::alarm_acknowledge()` for every raised alarm.
+    pub fn set_auto_acknowledge(&mut self, enable: bool) -> &mut Self {
+        self.auto_acknowledge = enable;
+        self
+    }
+
+    /// Messages to send when the alarm of the device at `address` is raised.
+    pub fn set_actions(&mut self, address: u8, alarm: u8, actions: Vec<Message>) -> &mut Self {
+        self.actions.insert((address, alarm), actions);
+        self
+    }
+
+    /// Also send the events to a channel.
+    pub fn set_sender(&mut self, sender: Sender<AlarmEvent>) -> &mut Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Last known alarm byte of the device at `address`.
+    pub fn alarms(&self, address: u8) -> u8 {
+        self.states.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Whether the alarm of the device at `address` is active.
+    pub fn is_raised(&self, address: u8, alarm: u8) -> bool {
+        (1..=ALARM_COUNT).contains(&alarm) && self.alarms(address) & (1 << (alarm - 1)) != 0
+    }
+
+    /// Update the alarm byte of the device at `address`, and return the
+    /// changes. No message is sent.
+    pub fn update(&mut self, address: u8, alarms: u8) -> Vec<AlarmEvent> {
+        let events = self.changes(address, alarms);
+        self.states.insert(address, alarms);
+        events
+    }
+
+    fn changes(&self, address: u8, alarms: u8) -> Vec<AlarmEvent> {
+        let changed = self.alarms(address) ^ alarms;
+        (1..=ALARM_COUNT)
+            .filter(|alarm| changed & (1 << (alarm - 1)) != 0)
+            .map(|alarm| AlarmEvent {
+                address,
+                alarm,
+                state: if alarms & (1 << (alarm - 1)) != 0 {
+                    AlarmState::Raised
+                } else {
+                    AlarmState::Cleared
+                },
+            })
+            .collect()
+    }
+
+    /// Handle a response. For a general response, the alarm changes are
+    /// reported, and for each raised alarm the configured actions are sent
+    /// and the alarm is acknowledged if enabled. Other responses are ignored.
+    ///
+    /// A raised alarm is recorded only once its actions and acknowledge are
+    /// sent. After an error, the alarms not handled yet are reported again by
+    /// the next response.
+    pub fn handle<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        response: &Response,
+    ) -> Result<Vec<AlarmEvent>> {
+        let events = match *response {
+            Response::General { address, alarms } => self.changes(address, alarms),
+            _ => return Ok(Vec::new()),
+        };
+
+        for event in events.iter() {
+            if event.state == AlarmState::Raised {
+                if let Some(actions) = self.actions.get(&(event.address, event.alarm)) {
+                    for action in actions.iter() {
+                        port.send_message(*action)?;
+                    }
+                }
+                if self.auto_acknowledge {
+                    port.send_message(Message::alarm_acknowledge(event.address, event.alarm)?)?;
+                }
+            }
+            self.commit(event);
+            if let Some(sender) = self.sender.as_ref() {
+                let _ = sender.send(*event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn commit(&mut self, event: &AlarmEvent) {
+        let bit = 1 << (event.alarm - 1);
+        let alarms = self.states.entry(event.address).or_insert(0);
+        match event.state {
+            AlarmState::Raised => *alarms |= bit,
+            AlarmState::Cleared => *alarms &= !bit,
+        }
+    }
+
+    /// Poll the devices at `addresses`: send the message built by `command`
+    /// to each device, and handle its general response like
+    /// [AlarmMonitor::handle()](struct.AlarmMonitor.html#method.handle).
+    ///
+    /// The command should not disturb the devices, like turning on cameras
+    /// which are already on. Polling stops at the first error, such as a
+    /// device not replying.
+    ///
+    /// ```rust,no_run
+    /// # use pelcodrs::*;
+    /// # fn example<T: std::io::Read + std::io::Write>(port: &mut PelcoDPort<T>) -> Result<()> {
+    /// let mut monitor = AlarmMonitor::new();
+    /// let events = monitor.poll(port, 1..=4, |address| {
+    ///     MessageBuilder::new(address)?.camera_on().finalize()
+    /// })?;
+    /// # Ok(())}
+    /// ```
+    pub fn poll<T, I, F>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        addresses: I,
+        command: F,
+    ) -> Result<Vec<AlarmEvent>>
+    where
+        T: Read + Write,
+        I: IntoIterator<Item = u8>,
+        F: Fn(u8) -> Result<Message>,
+    {
+        let mut events = Vec::new();
+        for address in addresses {
+            let response = port.transaction(command(address)?, ResponseKind::General)?;
+            events.extend(self.handle(port, &response)?);
+        }
+        Ok(events)
+    }
+
+    /// Receive and handle the general responses available on the port.
+    ///
+    /// Responses with a wrong checksum are skipped.
+    pub fn listen<T: Read + Write>(&mut self, port: &mut PelcoDPort<T>) -> Result<Vec<AlarmEvent>> {
+        let mut events = Vec::new();
+        loop {
+            match port.receive_response(ResponseKind::General) {
+                Ok(Some(response)) => events.extend(self.handle(port, &response)?),
+                Ok(None) => return Ok(events),
+                Err(e) => match e.kind() {
+                    ErrorKind::ChecksumMismatch => continue,
+                    _ => return Err(e),
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+
+/// Function of an auxiliary output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuxFunction {
+    /// Wiper.
+    Wiper,
+    /// Washer pump.
+    Washer,
+    /// Infrared illuminator.
+    IrIlluminator,
+    /// Heater.
+    Heater,
+    /// Generic relay, by auxiliary number.
+    Relay(u8),
+}
+
+/// Auxiliary outputs of a device model.
+///
+/// The table gives the number of auxiliary outputs, and which of them are
+/// wired to named functions. `AuxFunction::Relay(n)` is the auxiliary `n`,
+/// valid from 1 to the number of outputs.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// const MY_DOME: AuxTable = AuxTable::new(
+///     "My dome",
+///     2,
+///     &[(AuxFunction::Wiper, 1), (AuxFunction::Washer, 2)],
+/// );
+/// assert_eq!(2, MY_DOME.aux_id(AuxFunction::Washer).unwrap());
+/// assert_eq!(1, MY_DOME.aux_id(AuxFunction::Relay(1)).unwrap());
+/// let _ = MY_DOME.aux_id(AuxFunction::Relay(3)).unwrap_err();
+/// let _ = MY_DOME.aux_id(AuxFunction::Heater).unwrap_err();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuxTable {
+    name: &'static str,
+    count: u8,
+    functions: &'static [(AuxFunction, u8)],
+}
+
+impl AuxTable {
+    /// Eight relays without named function.
+    pub const GENERIC: AuxTable = AuxTable::new("Generic", 8, &[]);
+
+    /// New table with the given model name, number of auxiliary outputs and
+    /// named functions.
+    pub const fn new(
+        name: &'static str,
+        count: u8,
+        functions: &'static [(AuxFunction, u8)],
+    ) -> AuxTable {
+        AuxTable {
+            name,
+            count,
+            functions,
+        }
+    }
+
+    /// Name of the device model.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Number of auxiliary outputs.
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// Auxiliary number of the function. An error is returned if the model
+    /// does not have it.
+    pub fn aux_id(&self, function: AuxFunction) -> Result<u8> {
+        let aux_id = match function {
+            AuxFunction::Relay(aux_id) => Some(aux_id),
+            _ => self
+                .functions
+                .iter()
+                .find(|(f, _)| *f == function)
+                .map(|(_, aux_id)| *aux_id),
+        };
+        match aux_id {
+            Some(aux_id) if aux_id >= 1 && aux_id <= self.count => Ok(aux_id),
+            _ => Err(Error::new(
+                ErrorKind::InvalidValue,
+                &format!("No auxiliary {:?} on {}", function, self.name),
+            )),
+        }
+    }
+}
+
+impl Default for AuxTable {
+    fn default() -> Self {
+        AuxTable::GENERIC
+    }
+}
+
+/// Auxiliary outputs control, keeping track of their state per address.
+///
+/// For momentary actions, [Auxiliary::hold()](struct.Auxiliary.html#method.hold)
+/// returns a guard which clears the output when dropped, including when
+/// unwinding from a panic, so that relays are not left latched.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// const MY_DOME: AuxTable = AuxTable::new("My dome", 2, &[(AuxFunction::Wiper, 1)]);
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// let mut aux = Auxiliary::new(MY_DOME);
+///
+/// {
+///     let _wiper = aux.hold(&mut port, 10, AuxFunction::Wiper)?;
+///     // wiping...
+/// }
+/// assert!(!aux.is_set(10, AuxFunction::Wiper));
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Auxiliary {
+    table: AuxTable,
+    active: BTreeSet<(u8, u8)>,
+}
+
+impl Auxiliary {
+    pub fn new(table: AuxTable) -> Auxiliary {
+        Auxiliary {
+            table,
+            active: BTreeSet::new(),
+        }
+    }
+
+    /// Table of the auxiliary outputs.
+    pub fn table(&self) -> &AuxTable {
+        &self.table
+    }
+
+    /// Set the auxiliary output of the device at `address`.
+    pub fn set<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        function: AuxFunction,
+    ) -> Result<()> {
+        let aux_id = self.table.aux_id(function)?;
+        port.send_message(Message::set_auxiliary(address, 0, aux_id)?)?;
+        self.active.insert((address, aux_id));
+        Ok(())
+    }
+
+    /// Clear the auxiliary output of the device at `address`.
+    pub fn clear<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        function: AuxFunction,
+    ) -> Result<()> {
+        let aux_id = self.table.aux_id(function)?;
+        self.clear_id(port, address, aux_id)
+    }
+
+    /// Whether the auxiliary output of the device at `address` is set.
+    pub fn is_set(&self, address: u8, function: AuxFunction) -> bool {
+        match self.table.aux_id(function) {
+            Ok(aux_id) => self.active.contains(&(address, aux_id)),
+            Err(_) => false,
+        }
+    }
+
+    /// Auxiliary numbers of the outputs set on the device at `address`.
+    pub fn active(&self, address: u8) -> Vec<u8> {
+        self.active
+            .iter()
+            .filter(|(a, _)| *a == address)
+            .map(|(_, aux_id)| *aux_id)
+            .collect()
+    }
+
+    /// Set the auxiliary output, wait for `duration` with the clock of the
+    /// port, then clear it.
+    pub fn pulse<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        function: AuxFunction,
+        duration: Duration,
+    ) -> Result<()> {
+        let mut guard = self.hold(port, address, function)?;
+        guard.port().clock().sleep(duration);
+        guard.release()
+    }
+
+    /// Set the auxiliary output until the returned guard is released or
+    /// dropped.
+    pub fn hold<'a, T: Read + Write>(
+        &'a mut self,
+        port: &'a mut PelcoDPort<T>,
+        address: u8,
+        function: AuxFunction,
+    ) -> Result<AuxGuard<'a, T>> {
+        let aux_id = self.table.aux_id(function)?;
+        self.set(port, address, function)?;
+        Ok(AuxGuard {
+            aux: self,
+            port,
+            address,
+            aux_id,
+            released: false,
+        })
+    }
+
+    /// Clear all the auxiliary outputs set through this object.
+    pub fn clear_all<T: Read + Write>(&mut self, port: &mut PelcoDPort<T>) -> Result<()> {
+        let active: Vec<(u8, u8)> = self.active.iter().copied().collect();
+        for (address, aux_id) in active {
+            self.clear_id(port, address, aux_id)?;
+        }
+        Ok(())
+    }
+
+    fn clear_id<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        aux_id: u8,
+    ) -> Result<()> {
+        port.send_message(Message::clear_auxiliary(address, 0, aux_id)?)?;
+        self.active.remove(&(address, aux_id));
+        Ok(())
+    }
+}
+
+/// Guard of an auxiliary output set with
+/// [Auxiliary::hold()](struct.Auxiliary.html#method.hold). The output is
+/// cleared when the guard is dropped.
+#[derive(Debug)]
+pub struct AuxGuard<'a, T: Read + Write> {
+    aux: &'a mut Auxiliary,
+    port: &'a mut PelcoDPort<T>,
+    address: u8,
+    aux_id: u8,
+    released: bool,
+}
+
+impl<'a, T: Read + Write> AuxGuard<'a, T> {
+    /// Port used by the guard, for sending other messages while the output
+    /// is set.
+    pub fn port(&mut self) -> &mut PelcoDPort<T> {
+        self.port
+    }
+
+    /// Clear the output, returning the error that would be ignored on drop.
+    pub fn release(mut self) -> Result<()> {
+        self.released = true;
+        self.aux.clear_id(self.port, self.address, self.aux_id)
+    }
+}
+
+impl<'a, T: Read + Write> Drop for AuxGuard<'a, T> {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = self.aux.clear_id(self.port, self.address, self.aux_id);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::iter::FromIterator;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+use crate::rate::RateLimit;
+
+const FRAME_SIZE: usize = 7;
+
+/// Messages encoded one after the other into a single buffer, to be sent with
+/// [PelcoDPort::send_batch()](struct.PelcoDPort.html#method.send_batch).
+///
+/// The buffer is kept by `clear()`, so a batch can be reused without
+/// allocating again.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let mut batch = MessageBatch::with_capacity(16);
+/// batch.push(Message::clear_screen(10)?);
+/// for (column, character) in "PTZ 1".chars().enumerate() {
+///     batch.push(Message::write_char_to_screen(10, column as u8, character)?);
+/// }
+/// assert_eq!(6, batch.len());
+/// assert_eq!(42, batch.as_ref().len());
+///
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// port.send_batch(&batch)?;
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageBatch {
+    buffer: Vec<u8>,
+}
+
+impl MessageBatch {
+    pub fn new() -> MessageBatch {
+        MessageBatch::default()
+    }
+
+    /// New batch with room for `capacity` messages.
+    pub fn with_capacity(capacity: usize) -> MessageBatch {
+        MessageBatch {
+            buffer: Vec::with_capacity(capacity * FRAME_SIZE),
+        }
+    }
+
+    /// Add a message at the end of the batch.
+    pub fn push(&mut self, message: Message) {
+        self.buffer.extend_from_slice(message.as_ref());
+    }
+
+    /// Number of messages in the batch.
+    pub fn len(&self) -> usize {
+        self.buffer.len() / FRAME_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Remove all the messages, keeping the allocated buffer.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Messages of the batch, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Message> + '_ {
+        self.buffer
+            .chunks_exact(FRAME_SIZE)
+            .map(|frame| Message::try_from(frame).unwrap())
+    }
+}
+
+impl AsRef<[u8]> for MessageBatch {
+    fn as_ref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl Extend<Message> for MessageBatch {
+    fn extend<I: IntoIterator<Item = Message>>(&mut self, iter: I) {
+        for message in iter {
+            self.push(message);
+        }
+    }
+}
+
+impl FromIterator<Message> for MessageBatch {
+    fn from_iter<I: IntoIterator<Item = Message>>(iter: I) -> Self {
+        let mut batch = MessageBatch::new();
+        batch.extend(iter);
+        batch
+    }
+}
+
+impl<T: Read + Write> PelcoDPort<T> {
+    /// Send all the messages of a batch.
+    ///
+    /// Without rate limit, the batch is written in a single call, which saves
+    /// a lot of time on the network transports. Otherwise the messages are
+    /// sent one by one, with the gaps required by the
+    /// [RateLimit](struct.RateLimit.html).
+    pub fn send_batch(&mut self, batch: &MessageBatch) -> Result<()> {
+        if self.rate_limit() != RateLimit::default() {
+            for message in batch.iter() {
+                self.send_message(message)?;
+            }
+            return Ok(());
+        }
+
+        self.write_all(batch.as_ref())?;
+        for message in batch.iter() {
+            self.record_sent(&message);
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(frames = batch.len(), "batch sent");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use std::io::{BufRead, Read, Write};
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::error::*;
+
+const CAPTURE_HEADER: &str = "# pelcodrs capture v1";
+
+/// Direction of the captured bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureDirection {
+    /// Bytes sent to the devices.
+    Sent,
+    /// Bytes received from the devices.
+    Received,
+}
+
+/// Bytes captured by a [RecordingTransport](struct.RecordingTransport.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// Time since the start of the capture.
+    pub timestamp: Duration,
+    pub direction: CaptureDirection,
+    pub bytes: Vec<u8>,
+}
+
+impl fmt::Display for CaptureRecord {
+    /// Line of the record in the capture file, without the line feed.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            CaptureDirection::Sent => "TX",
+            CaptureDirection::Received => "RX",
+        };
+        write!(f, "{} {}", self.timestamp.as_micros(), direction)?;
+        for byte in self.bytes.iter() {
+            write!(f, " {:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writer of capture files.
+///
+/// The capture file format is line-based text. The first line is the header
+/// `# pelcodrs capture v1`, and the other lines starting with `#` are
+/// comments. Each record line holds the timestamp in microseconds since the
+/// start of the capture, the direction `TX` (sent) or `RX` (received), and
+/// the bytes in hexadecimal, separated by spaces:
+///
+/// ```text
+/// # pelcodrs capture v1
+/// 0 TX FF 0A 00 07 00 05 16
+/// 12500 RX FF 0A 00 0A
+/// ```
+#[derive(Debug)]
+pub struct CaptureWriter<W: Write, C: Clock> {
+    writer: W,
+    clock: C,
+    start: Duration,
+}
+
+impl<W: Write, C: Clock> CaptureWriter<W, C> {
+    /// New capture, starting now. The header is written immediately.
+    pub fn new(mut writer: W, clock: C) -> Result<CaptureWriter<W, C>> {
+        writeln!(writer, "{}", CAPTURE_HEADER)?;
+        let start = clock.now();
+        Ok(CaptureWriter {
+            writer,
+            clock,
+            start,
+        })
+    }
+
+    /// Record bytes with the current timestamp.
+    pub fn record(&mut self, direction: CaptureDirection, bytes: &[u8]) -> std::io::Result<()> {
+        let record = CaptureRecord {
+            timestamp: self.clock.now().saturating_sub(self.start),
+            direction,
+            bytes: bytes.to_vec(),
+        };
+        writeln!(self.writer, "{}", record)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Transport wrapper recording all the bytes sent and received into a
+/// capture file.
+///
+/// A failure of the capture does not change the result of the reads and
+/// writes on the transport. The recording stops at the first error, which is
+/// available from
+/// [RecordingTransport::capture_error()](struct.RecordingTransport.html#method.capture_error).
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let serial = std::io::Cursor::new(Vec::new()); // would be the serial port
+/// let capture = CaptureWriter::new(Vec::new(), SystemClock::new())?;
+/// let mut port = PelcoDPort::new(RecordingTransport::new(serial, capture));
+/// port.send_message(Message::go_to_preset(10, 5)?)?;
+///
+/// let (_, capture) = port.into_inner().into_inner();
+/// let text = String::from_utf8(capture.into_inner()?).unwrap();
+/// assert!(text.lines().nth(1).unwrap().ends_with("TX FF 0A 00 07 00 05 16"));
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RecordingTransport<T: Read + Write, W: Write, C: Clock> {
+    inner: T,
+    capture: CaptureWriter<W, C>,
+    capture_error: Option<std::io::Error>,
+}
+
+impl<T: Read + Write, W: Write, C: Clock> RecordingTransport<T, W, C> {
+    pub fn new(inner: T, capture: CaptureWriter<W, C>) -> RecordingTransport<T, W, C> {
+        RecordingTransport {
+            inner,
+            capture,
+            capture_error: None,
+        }
+    }
+
+    /// Error which stopped the recording, if any.
+    pub fn capture_error(&self) -> Option<&std::io::Error> {
+        self.capture_error.as_ref()
+    }
+
+    fn record(&mut self, direction: CaptureDirection, bytes: &[u8]) {
+        if bytes.is_empty() || self.capture_error.is_some() {
+            return;
+        }
+        if let Err(e) = self.capture.record(direction, bytes) {
+            self.capture_error = Some(e);
+        }
+    }
+
+    /// Return the transport and the capture writer.
+    pub fn into_inner(self) -> (T, CaptureWriter<W, C>) {
+        (self.inner, self.capture)
+    }
+}
+
+impl<T: Read + Write, W: Write, C: Clock> Read for RecordingTransport<T, W, C> {
+    fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        let count = self.inner.read(buf)?;
+        self.record(CaptureDirection::Received, &buf[..count]);
+        Ok(count)
+    }
+}
+
+impl<T: Read + Write, W: Write, C: Clock> Write for RecordingTransport<T, W, C> {
+    fn write(&mut self, buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
+        let count = self.inner.write(buf)?;
+        self.record(CaptureDirection::Sent, &buf[..count]);
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Reader of capture files, iterating over the records.
+///
+/// See [CaptureWriter](struct.CaptureWriter.html) for the file format.
+#[derive(Debug)]
+pub struct CaptureReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    line_no: usize,
+}
+
+impl<R: BufRead> CaptureReader<R> {
+    pub fn new(reader: R) -> CaptureReader<R> {
+        CaptureReader {
+            lines: reader.lines(),
+            line_no: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CaptureReader<R> {
+    type Item = Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line_no += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            return Some(parse_record(line).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Parse,
+                    &format!("line {}: invalid capture record", self.line_no),
+                )
+            }));
+        }
+        None
+    }
+}
+
+fn parse_record(line: &str) -> Option<CaptureRecord> {
+    let mut tokens = line.split_whitespace();
+    let timestamp = Duration::from_micros(tokens.next()?.parse().ok()?);
+    let direction = match tokens.next()? {
+        "TX" => CaptureDirection::Sent,
+        "RX" => CaptureDirection::Received,
+        _ => return None,
+    };
+    let bytes = tokens
+        .map(|t| u8::from_str_radix(t, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(CaptureRecord {
+        timestamp,
+        direction,
+        bytes,
+    })
+}
+
+/// Replay the sent bytes of captured records into `output`, with their
+/// original timing divided by `speed`.
+///
+/// The received bytes are ignored. For example, a speed of 2.0 replays twice
+/// as fast as captured.
+pub fn replay_capture<I, W, C>(records: I, output: &mut W, clock: &C, speed: f64) -> Result<()>
+where
+    I: IntoIterator<Item = CaptureRecord>,
+    W: Write,
+    C: Clock,
+{
+    if !(speed > 0.0 && speed.is_finite()) {
+        return Err(Error::new(ErrorKind::InvalidValue, "Invalid replay speed"));
+    }
+
+    let start = clock.now();
+    let mut origin = None;
+    for record in records {
+        if record.direction != CaptureDirection::Sent {
+            continue;
+        }
+        let origin = *origin.get_or_insert(record.timestamp);
+        let offset = record.timestamp.saturating_sub(origin).div_f64(speed);
+        clock.sleep((start + offset).saturating_sub(clock.now()));
+        output.write_all(&record.bytes)?;
+    }
+    output.flush()?;
+    Ok(())
+}
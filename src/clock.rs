@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of time used by the timed features of the crate.
+///
+/// [SystemClock](struct.SystemClock.html) is the clock to use with real
+/// devices. [ManualClock](struct.ManualClock.html) allows deterministic tests
+/// without actual sleeps.
+///
+/// Since the clock is stored in [PelcoDPort](struct.PelcoDPort.html), which
+/// implements `Debug`, the trait requires `Debug`. This is a breaking change
+/// for the clocks implemented outside the crate, which need to derive or
+/// implement `Debug`.
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// Time elapsed since an arbitrary, fixed origin.
+    fn now(&self) -> Duration;
+
+    /// Block for the given duration.
+    fn sleep(&self, duration: Duration);
+}
+
+/// Clock following the system monotonic time.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// Clock whose time only moves when told to.
+///
+/// Sleeping advances the time immediately. Clones share the same time, so
+/// that a test can keep a handle on a clock given to another object.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// let clock = ManualClock::new();
+/// let handle = clock.clone();
+/// clock.sleep(Duration::from_secs(2));
+/// handle.advance(Duration::from_secs(1));
+/// assert_eq!(Duration::from_secs(3), clock.now());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// Move the time forward.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+
+    /// Set the current time.
+    pub fn set(&self, now: Duration) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
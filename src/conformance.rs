@@ -0,0 +1,95 @@
+//! Golden vectors of the Pelco D commands, for checking the encoders.
+//!
+//! The vectors cover every opcode supported by [Message](../struct.Message.html),
+//! with the variants of the shutter speed and the adjustment encodings. They
+//! are read from the file `tests/golden_vectors.txt` of the crate, against
+//! which the constructors of this crate are tested, and can be used to check
+//! other implementations:
+//!
+//! ```rust
+//! use pelcodrs::conformance;
+//!
+//! for vector in conformance::vectors() {
+//!     let frame = vector.command.parse::<pelcodrs::Message>().unwrap();
+//!     vector.check(frame.as_ref()).unwrap();
+//! }
+//! ```
+
+use crate::error::*;
+use crate::message::Message;
+use crate::text::hex;
+
+/// Text of the vectors file: one vector per line, made of the name, the
+/// command in the mnemonic format of `Message` and the bytes of the frame in
+/// hexadecimal, separated by `|`. The lines starting with `#` are comments.
+pub const VECTORS: &str = include_str!("../tests/golden_vectors.txt");
+
+/// Expected frame of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vector {
+    /// Name of the vector, like `go_to_preset`.
+    pub name: &'static str,
+    /// Command in the mnemonic format, like `addr=10 GOTO_PRESET 5`.
+    pub command: &'static str,
+    /// Bytes of the frame.
+    pub frame: [u8; 7],
+}
+
+impl Vector {
+    /// Expected frame, as a message.
+    pub fn message(&self) -> Message {
+        Message::from(self.frame)
+    }
+
+    /// Check the frame produced by an encoder. An error of kind
+    /// `ErrorKind::InvalidValue` describes the difference.
+    pub fn check(&self, frame: &[u8]) -> Result<()> {
+        if frame == self.frame {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidValue,
+                &format!(
+                    "{}: expected {:X}, got {}",
+                    self.name,
+                    self.message(),
+                    hex(frame)
+                ),
+            ))
+        }
+    }
+}
+
+/// All the vectors, in the order of the file.
+pub fn vectors() -> Vec<Vector> {
+    VECTORS
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| parse_vector(line).expect("Invalid golden vector"))
+        .collect()
+}
+
+/// Vector with the given name.
+pub fn vector(name: &str) -> Option<Vector> {
+    vectors().into_iter().find(|vector| vector.name == name)
+}
+
+fn parse_vector(line: &'static str) -> Option<Vector> {
+    let mut fields = line.split('|').map(str::trim);
+    let name = fields.next()?;
+    let command = fields.next()?;
+    let mut frame = [0u8; 7];
+    let mut bytes = fields.next()?.split_whitespace();
+    for byte in frame.iter_mut() {
+        *byte = u8::from_str_radix(bytes.next()?, 16).ok()?;
+    }
+    if bytes.next().is_some() || fields.next().is_some() {
+        return None;
+    }
+    Some(Vector {
+        name,
+        command,
+        frame,
+    })
+}
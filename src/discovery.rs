@@ -0,0 +1,215 @@
+use std::io::{Read, Write};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::address::Address;
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+use crate::rate::RateLimit;
+use crate::response::*;
+use crate::retry::RetryPolicy;
+
+/// Device found by a [Discovery](struct.Discovery.html) scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredDevice {
+    pub address: u8,
+    /// Baud rate of the device, when the scan tried several rates.
+    pub baud: Option<u32>,
+    /// Response of the device to the query.
+    pub response: Response,
+    /// Time between the query and the response.
+    pub latency: Duration,
+}
+
+impl DiscoveredDevice {
+    /// Part number of the device, if it answered with a query response.
+    pub fn part_number(&self) -> Option<String> {
+        self.response.part_number()
+    }
+}
+
+/// Result of a [Discovery](struct.Discovery.html) scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    pub devices: Vec<DiscoveredDevice>,
+    /// Addresses which answered with an invalid or unexpected response.
+    pub invalid: Vec<u8>,
+    /// Number of queries sent.
+    pub probed: usize,
+    /// Duration of the scan.
+    pub duration: Duration,
+    /// Whether the scan was cancelled before the end.
+    pub cancelled: bool,
+}
+
+/// Scan of the bus for the devices answering a query.
+///
+/// Each address of the range is sent a query, which does not change the
+/// state of the devices, and the devices answering in time are listed in the
+/// returned [Inventory](struct.Inventory.html).
+///
+/// ```rust, ignore
+/// let mut port = PelcoDPort::open("/dev/ttyUSB0", Baud::B2400)?;
+/// let inventory = Discovery::new().set_addresses(1..=32).run(&mut port)?;
+/// for device in inventory.devices.iter() {
+///     println!("{}: {:?}", device.address, device.part_number());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    addresses: RangeInclusive<u8>,
+    timeout: Duration,
+    interval: Duration,
+    kind: ResponseKind,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Discovery {
+    /// New scan of the addresses 1 to 254, waiting 200 ms for each response,
+    /// with at least 50 ms between two queries. The address 255 is left out,
+    /// since some installations use it as a broadcast address.
+    pub fn new() -> Discovery {
+        Discovery {
+            addresses: 1..=254,
+            timeout: Duration::from_millis(200),
+            interval: Duration::from_millis(50),
+            kind: ResponseKind::Query,
+            cancel: None,
+        }
+    }
+
+    /// Range of scanned addresses. The broadcast addresses are skipped.
+    pub fn set_addresses(&mut self, addresses: RangeInclusive<u8>) -> &mut Self {
+        self.addresses = addresses;
+        self
+    }
+
+    /// Time to wait for the response of each device.
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Minimum time between two queries.
+    pub fn set_interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Kind of the expected responses, `ResponseKind::Query` by default.
+    pub fn set_response_kind(&mut self, kind: ResponseKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Flag stopping the scan when set, for example from another thread.
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) -> &mut Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Scan the addresses at the current baud rate of the port.
+    pub fn run<T: Read + Write>(&self, port: &mut PelcoDPort<T>) -> Result<Inventory> {
+        let mut inventory = Inventory::default();
+        self.scan(port, None, &mut inventory)?;
+        Ok(inventory)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        match self.cancel.as_ref() {
+            Some(flag) => flag.load(Ordering::SeqCst),
+            None => false,
+        }
+    }
+
+    fn scan<T: Read + Write>(
+        &self,
+        port: &mut PelcoDPort<T>,
+        baud: Option<u32>,
+        inventory: &mut Inventory,
+    ) -> Result<()> {
+        let limit = port.rate_limit();
+        port.set_rate_limit(RateLimit {
+            min_gap: limit.min_gap.max(self.interval),
+            ..limit
+        });
+        let result = self.scan_addresses(port, baud, inventory);
+        port.set_rate_limit(limit);
+        result
+    }
+
+    fn scan_addresses<T: Read + Write>(
+        &self,
+        port: &mut PelcoDPort<T>,
+        baud: Option<u32>,
+        inventory: &mut Inventory,
+    ) -> Result<()> {
+        let start = port.clock().now();
+        let policy = RetryPolicy::no_retry(self.timeout);
+
+        for address in self.addresses.clone() {
+            if Address::from_byte(address).is_broadcast() {
+                continue;
+            }
+            if self.is_cancelled() {
+                inventory.cancelled = true;
+                break;
+            }
+
+            // Wait for the rate limit here, to measure only the latency
+            let delay = port.send_delay(address);
+            port.clock().sleep(delay);
+            let sent = port.clock().now();
+
+            inventory.probed += 1;
+            match port.transaction_with(Message::query_to(address), self.kind, &policy) {
+                Ok(response) => inventory.devices.push(DiscoveredDevice {
+                    address,
+                    baud,
+                    response,
+                    latency: port.clock().now() - sent,
+                }),
+                Err(e) => match e.kind() {
+                    ErrorKind::Timeout => (),
+                    ErrorKind::ChecksumMismatch | ErrorKind::UnexpectedResponse => {
+                        inventory.invalid.push(address)
+                    }
+                    _ => return Err(e),
+                },
+            }
+        }
+
+        inventory.duration += port.clock().now() - start;
+        Ok(())
+    }
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Discovery::new()
+    }
+}
+
+#[cfg(all(feature = "serial", unix))]
+impl Discovery {
+    /// Scan the addresses at each of the given baud rates.
+    pub fn run_bauds(
+        &self,
+        port: &mut PelcoDPort<crate::serial::SerialTransport>,
+        bauds: &[crate::serial::Baud],
+    ) -> Result<Inventory> {
+        let mut inventory = Inventory::default();
+        for baud in bauds.iter() {
+            if self.is_cancelled() {
+                inventory.cancelled = true;
+                break;
+            }
+            port.set_baud(*baud)?;
+            self.scan(port, Some(baud.rate()), &mut inventory)?;
+        }
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::port::is_no_data;
+
+/// Control of the transmitter of a half-duplex line, like the DE/RE line of
+/// an RS-485 transceiver. The transport of the port is given for controls
+/// using it, like [RtsControl](struct.RtsControl.html).
+pub trait DirectionControl<T> {
+    /// Enable the transmitter if `transmit` is `true`, or enable the
+    /// receiver otherwise.
+    fn set_transmit(&mut self, transport: &mut T, transmit: bool) -> std::io::Result<()>;
+}
+
+/// Transport with a Request To Send line.
+pub trait RequestToSend {
+    fn set_request_to_send(&mut self, level: bool) -> std::io::Result<()>;
+}
+
+/// Direction control by the RTS line of the transport, which is high while
+/// transmitting unless inverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RtsControl {
+    pub inverted: bool,
+}
+
+impl<T: RequestToSend> DirectionControl<T> for RtsControl {
+    fn set_transmit(&mut self, transport: &mut T, transmit: bool) -> std::io::Result<()> {
+        transport.set_request_to_send(transmit != self.inverted)
+    }
+}
+
+/// Direction control by a callback, for example driving a GPIO.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// let control = CallbackControl::new(|transmit| {
+///     // Set the GPIO of the DE line to `transmit`
+///     Ok(())
+/// });
+/// port.set_half_duplex(control, HalfDuplex::default());
+/// ```
+pub struct CallbackControl<F: FnMut(bool) -> std::io::Result<()>> {
+    callback: F,
+}
+
+impl<F: FnMut(bool) -> std::io::Result<()>> CallbackControl<F> {
+    pub fn new(callback: F) -> CallbackControl<F> {
+        CallbackControl { callback }
+    }
+}
+
+impl<F: FnMut(bool) -> std::io::Result<()>> fmt::Debug for CallbackControl<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CallbackControl")
+    }
+}
+
+impl<T, F: FnMut(bool) -> std::io::Result<()>> DirectionControl<T> for CallbackControl<F> {
+    fn set_transmit(&mut self, _transport: &mut T, transmit: bool) -> std::io::Result<()> {
+        (self.callback)(transmit)
+    }
+}
+
+/// Settings of the half-duplex mode of a
+/// [PelcoDPort](struct.PelcoDPort.html).
+///
+/// When the echo is discarded, the echoed bytes are read back right after
+/// each write, so the transport must have a read timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HalfDuplex {
+    /// Wait after enabling the transmitter, before writing.
+    pub tx_delay: Duration,
+    /// Wait after writing, before enabling the receiver. It should cover the
+    /// transmission of the bytes buffered by the transport.
+    pub rx_delay: Duration,
+    /// Whether the transport receives its own transmitted bytes, which must
+    /// be discarded.
+    pub discard_echo: bool,
+}
+
+impl Default for HalfDuplex {
+    /// No delays, and no echo.
+    fn default() -> Self {
+        HalfDuplex {
+            tx_delay: Duration::from_secs(0),
+            rx_delay: Duration::from_secs(0),
+            discard_echo: false,
+        }
+    }
+}
+
+/// Half-duplex mode of a port, with its direction control.
+pub(crate) struct HalfDuplexLine<T> {
+    control: Box<dyn DirectionControl<T> + Send>,
+    settings: HalfDuplex,
+    // Failure to enable the receiver after a successful write, returned by
+    // the next write
+    direction_error: Option<std::io::Error>,
+}
+
+impl<T> fmt::Debug for HalfDuplexLine<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HalfDuplexLine")
+            .field("settings", &self.settings)
+            .field("direction_error", &self.direction_error)
+            .finish()
+    }
+}
+
+impl<T: Read + Write> HalfDuplexLine<T> {
+    pub(crate) fn new<D: DirectionControl<T> + Send + 'static>(
+        control: D,
+        settings: HalfDuplex,
+    ) -> HalfDuplexLine<T> {
+        HalfDuplexLine {
+            control: Box::new(control),
+            settings,
+            direction_error: None,
+        }
+    }
+
+    /// Write with the transmitter enabled. The received bytes which are not
+    /// the echo of the written ones are added to `received`.
+    ///
+    /// If the receiver cannot be enabled after a successful write, the
+    /// written count is still returned, and the error is returned by the next
+    /// write.
+    pub(crate) fn write(
+        &mut self,
+        transport: &mut T,
+        clock: &dyn Clock,
+        buf: &[u8],
+        received: &mut Vec<u8>,
+    ) -> std::io::Result<usize> {
+        if let Some(e) = self.direction_error.take() {
+            return Err(e);
+        }
+        self.control.set_transmit(transport, true)?;
+        clock.sleep(self.settings.tx_delay);
+        let result = transport.write(buf).and_then(|count| {
+            transport.flush()?;
+            Ok(count)
+        });
+        clock.sleep(self.settings.rx_delay);
+        let released = self.control.set_transmit(transport, false);
+        let count = result?;
+        if let Err(e) = released {
+            self.direction_error = Some(e);
+            return Ok(count);
+        }
+
+        if self.settings.discard_echo {
+            let mut echo = vec![0u8; count];
+            let mut length = 0;
+            while length < count {
+                match transport.read(&mut echo[length..]) {
+                    Ok(0) => break,
+                    Ok(n) => length += n,
+                    Err(e) if is_no_data(&e) => break,
+                    Err(e) => return Err(e),
+                }
+            }
+            let matching = echo[..length]
+                .iter()
+                .zip(buf.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            received.extend_from_slice(&echo[matching..length]);
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(echo = matching, missing = count - length, "echo discarded");
+        }
+
+        Ok(count)
+    }
+}
@@ -4,6 +4,25 @@ pub enum ErrorKind {
     /// Invalid parameter.
     InvalidValue,
 
+    /// The preset number is reserved for a special function on the target
+    /// device.
+    ReservedPreset(crate::preset::SpecialPreset),
+
+    /// The checksum of a received response is wrong.
+    ChecksumMismatch,
+
+    /// No response was received in time.
+    Timeout,
+
+    /// The received response does not match the request.
+    UnexpectedResponse,
+
+    /// Invalid text representation.
+    Parse,
+
+    /// Invalid script, at the given line number (starting from 1).
+    Script { line: usize },
+
     /// IO error
     Io(std::io::Error),
 }
@@ -22,6 +41,11 @@ impl Error {
             description: String::from(description),
         }
     }
+
+    /// Kind of the error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
 }
 
 impl std::error::Error for Error {
@@ -0,0 +1,229 @@
+//! Constant constructors of the messages which cannot fail.
+//!
+//! They take a valid [Address](../enum.Address.html), and can be evaluated
+//! at compile time to build static tables of frames:
+//!
+//! ```rust
+//! use pelcodrs::{frames, Address, Message};
+//!
+//! const CAMERA: Address = match Address::unit(10) {
+//!     Some(address) => address,
+//!     None => panic!("invalid address"),
+//! };
+//!
+//! static SHIFT_CHANGE: [Message; 3] = [
+//!     frames::stop(Address::Broadcast),
+//!     frames::clear_screen(CAMERA),
+//!     frames::run_pattern(CAMERA, 1),
+//! ];
+//!
+//! assert_eq!(&[0xFF, 0x0A, 0x00, 0x17, 0x00, 0x00, 0x21], SHIFT_CHANGE[1].as_ref());
+//! ```
+//!
+//! The constructors of [Message](../struct.Message.html) returning a `Result`
+//! are the same, after validating their arguments.
+
+use crate::address::Address;
+use crate::message::*;
+use crate::preset::{PresetTable, SpecialPreset};
+
+const fn extended(address: Address, words: [u8; 4]) -> Message {
+    Message::from_bytes(address.byte(), words)
+}
+
+const fn on_off(ctrl: OnOff, on: u8, off: u8) -> u8 {
+    match ctrl {
+        OnOff::On => on,
+        OnOff::Off => off,
+        OnOff::Value(x) => x,
+    }
+}
+
+const fn adjust(address: Address, opcode: u8, ctrl: AdjustmentValue) -> Message {
+    let (cmd, data): (u8, [u8; 2]) = match ctrl {
+        AdjustmentValue::New(value) => (0, value.to_be_bytes()),
+        AdjustmentValue::Delta(value) => (1, value.to_be_bytes()),
+    };
+    extended(address, [cmd, opcode, data[0], data[1]])
+}
+
+/// Stop all the motions.
+pub const fn stop(address: Address) -> Message {
+    extended(address, [0x00, 0x00, 0x00, 0x00])
+}
+
+/// Call the special preset "rotate 180 degrees" of
+/// [PresetTable::GENERIC](../struct.PresetTable.html#associatedconstant.GENERIC).
+pub const fn flip_180(address: Address) -> Message {
+    generic_special(address, SpecialPreset::Flip180)
+}
+
+/// Call the special preset "Go To Zero Pan" of
+/// [PresetTable::GENERIC](../struct.PresetTable.html#associatedconstant.GENERIC).
+pub const fn go_to_zero_pan(address: Address) -> Message {
+    generic_special(address, SpecialPreset::GoToZeroPan)
+}
+
+const fn generic_special(address: Address, special: SpecialPreset) -> Message {
+    match PresetTable::GENERIC.preset_id(special) {
+        Some(preset_id) => extended(address, [0x00, 0x07, 0x00, preset_id]),
+        None => panic!("special preset missing from the generic table"),
+    }
+}
+
+/// Set Auxiliary.
+pub const fn set_auxiliary(address: Address, sub_opcode: u8, aux_id: u8) -> Message {
+    extended(address, [sub_opcode, 0x09, 0x00, aux_id])
+}
+
+/// Clear Auxiliary.
+pub const fn clear_auxiliary(address: Address, sub_opcode: u8, aux_id: u8) -> Message {
+    extended(address, [sub_opcode, 0x0B, 0x00, aux_id])
+}
+
+/// Reset.
+pub const fn remote_reset(address: Address) -> Message {
+    extended(address, [0x00, 0x0F, 0x00, 0x00])
+}
+
+/// Set Zone Start.
+pub const fn set_zone_start(address: Address, zone_id: u8) -> Message {
+    extended(address, [0x00, 0x11, 0x00, zone_id])
+}
+
+/// Set Zone End.
+pub const fn set_zone_end(address: Address, zone_id: u8) -> Message {
+    extended(address, [0x00, 0x13, 0x00, zone_id])
+}
+
+/// Clear Screen.
+pub const fn clear_screen(address: Address) -> Message {
+    extended(address, [0x00, 0x17, 0x00, 0x00])
+}
+
+/// Alarm Acknowledge.
+pub const fn alarm_acknowledge(address: Address, alarm_no: u8) -> Message {
+    extended(address, [0x00, 0x19, 0x00, alarm_no])
+}
+
+/// Zone Scan On.
+pub const fn zone_scan_on(address: Address) -> Message {
+    extended(address, [0x00, 0x1B, 0x00, 0x00])
+}
+
+/// Zone Scan Off.
+pub const fn zone_scan_off(address: Address) -> Message {
+    extended(address, [0x00, 0x1D, 0x00, 0x00])
+}
+
+/// Record Pattern Start.
+pub const fn set_pattern_start(address: Address, pattern_id: u8) -> Message {
+    extended(address, [0x00, 0x1F, 0x00, pattern_id])
+}
+
+/// Record Pattern End.
+pub const fn set_pattern_stop(address: Address, pattern_id: u8) -> Message {
+    extended(address, [0x00, 0x21, 0x00, pattern_id])
+}
+
+/// Run Pattern.
+pub const fn run_pattern(address: Address, pattern_id: u8) -> Message {
+    extended(address, [0x00, 0x23, 0x00, pattern_id])
+}
+
+/// Set Zoom Speed.
+pub const fn set_zoom_speed(address: Address, speed: ZoomSpeed) -> Message {
+    extended(address, [0x00, 0x25, 0x00, speed as u8])
+}
+
+/// Set Focus Speed.
+pub const fn set_focus_speed(address: Address, speed: FocusSpeed) -> Message {
+    extended(address, [0x00, 0x27, 0x00, speed as u8])
+}
+
+/// Reset Camera to Defaults.
+pub const fn reset_camera_to_defaults(address: Address) -> Message {
+    extended(address, [0x00, 0x29, 0x00, 0x00])
+}
+
+/// Auto Focus.
+pub const fn auto_focus(address: Address, ctrl: AutoCtrl) -> Message {
+    extended(address, [0x00, 0x2B, 0x00, ctrl as u8])
+}
+
+/// Auto Iris.
+pub const fn auto_iris(address: Address, ctrl: AutoCtrl) -> Message {
+    extended(address, [0x00, 0x2D, 0x00, ctrl as u8])
+}
+
+/// AGC.
+pub const fn agc(address: Address, ctrl: AutoCtrl) -> Message {
+    extended(address, [0x00, 0x2F, 0x00, ctrl as u8])
+}
+
+/// Backlight Compensation.
+pub const fn backlight_compensation(address: Address, ctrl: OnOff) -> Message {
+    extended(address, [0x00, 0x31, 0x00, on_off(ctrl, 2, 1)])
+}
+
+/// Auto White Balance.
+pub const fn auto_white_balance(address: Address, ctrl: OnOff) -> Message {
+    extended(address, [0x00, 0x33, 0x00, on_off(ctrl, 1, 2)])
+}
+
+/// Enable Device Phase Delay Mode.
+pub const fn enable_device_phase_delay_mode(address: Address) -> Message {
+    extended(address, [0x00, 0x35, 0x00, 0x00])
+}
+
+/// Set Shutter Speed.
+pub const fn set_shutter_speed(address: Address, ctrl: ShutterSpeed) -> Message {
+    let data: [u8; 2] = match ctrl {
+        ShutterSpeed::Bytes(d1, d2) => [d1, d2],
+        ShutterSpeed::DefaultValue => [0, 0],
+        ShutterSpeed::Increment => [0, 1],
+        ShutterSpeed::Decrement => [0, 2],
+        ShutterSpeed::PAL => [0, 50],
+        ShutterSpeed::NTSC => [0, 60],
+        ShutterSpeed::Value(secs) => secs.to_be_bytes(),
+        ShutterSpeed::AutoShutter => [0, 0],
+        ShutterSpeed::Index(idx) => [0, idx],
+    };
+    extended(address, [0x00, 0x37, data[0], data[1]])
+}
+
+/// Adjust Line Lock Phase Delay.
+pub const fn adjust_line_lock_phase_delay(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x39, ctrl)
+}
+
+/// Adjust White Balance (R-B)
+pub const fn adjust_white_balance_rb(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x3B, ctrl)
+}
+
+/// Adjust White Balance (M-G)
+pub const fn adjust_white_balance_mg(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x3D, ctrl)
+}
+
+/// Adjust Gain.
+pub const fn adjust_gain(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x3F, ctrl)
+}
+
+/// Adjust Auto-Iris Level
+pub const fn adjust_auto_iris_level(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x41, ctrl)
+}
+
+/// Adjust Auto-Iris Peak Value.
+pub const fn adjust_auto_iris_peak(address: Address, ctrl: AdjustmentValue) -> Message {
+    adjust(address, 0x43, ctrl)
+}
+
+/// Query. See [Message::query()](../struct.Message.html#method.query) about
+/// its address.
+pub const fn query(address: Address) -> Message {
+    extended(address, [0x00, 0x45, 0x00, 0x00])
+}
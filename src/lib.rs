@@ -1,18 +1,111 @@
 //! Library for handling Pelco D, a protocol used for PTZ camera control.
 //!
 //! * [Message](struct.Message.html) is the type defining a control message.
+//!   It is sent to a single unit or broadcast to all the devices, as given by
+//!   its [Address](enum.Address.html). The messages which cannot fail are
+//!   also built by the constant functions of the [frames](frames/index.html)
+//!   module.
 //!
 //! * [PelcoDPort](struct.PelcoDPort.html) can be used for sending the messages
-//!   to the device.
+//!   to the device. It keeps [PortMetrics](struct.PortMetrics.html) about the
+//!   traffic, and runs request/response transactions according to a
+//!   [RetryPolicy](struct.RetryPolicy.html).
+//!
+//! * [MessageBatch](struct.MessageBatch.html) sends many messages at once.
+//!
+//! * [RateLimit](struct.RateLimit.html) limits the frames sent by a port.
+//!
+//! * [SendQueue](struct.SendQueue.html) prioritizes the messages, and
+//!   [CoalescingQueue](struct.CoalescingQueue.html) collapses the motion
+//!   updates.
+//!
+//! * [DirectionControl](trait.DirectionControl.html) drives the transmitter
+//!   on half-duplex lines like RS-485.
+//!
+//! * [PresetTable](struct.PresetTable.html) describes the presets reserved for
+//!   special functions by a device model.
+//!
+//! * [Tour](struct.Tour.html) runs a cyclic sequence of presets on a device.
+//!
+//! * [PatternRecorder](struct.PatternRecorder.html) records motion patterns in
+//!   the devices.
+//!
+//! * [Auxiliary](struct.Auxiliary.html) controls the auxiliary outputs (wiper,
+//!   relays...) of the devices.
+//!
+//! * [Zone](struct.Zone.html) configures the zones of a device.
+//!
+//! * [Response](enum.Response.html) is a response received from a device, and
+//!   [AlarmMonitor](struct.AlarmMonitor.html) handles the alarms it reports.
+//!
+//! * [Discovery](struct.Discovery.html) finds the devices connected to a bus.
+//!
+//! * The [conformance](conformance/index.html) module provides golden vectors
+//!   of the frames, for checking Pelco D encoders.
+//!
+//! * With the `testing` feature, the [testing](testing/index.html) module
+//!   provides a mock transport for testing the code using a port.
+//!
+//! * [Script](struct.Script.html) describes camera sequences in a simple text
+//!   language.
+//!
+//! * [RecordingTransport](struct.RecordingTransport.html) captures the traffic
+//!   of a port into a file, which can be read back with
+//!   [CaptureReader](struct.CaptureReader.html) and replayed.
 //!
 
 #[macro_use]
 extern crate bitflags;
 
+pub use address::*;
+pub use alarm::*;
+pub use auxiliary::*;
+pub use batch::*;
+pub use capture::*;
+pub use clock::*;
+pub use discovery::*;
+pub use duplex::*;
 pub use error::*;
 pub use message::*;
+pub use metrics::*;
+pub use pattern::*;
 pub use port::*;
+pub use preset::*;
+pub use queue::*;
+pub use rate::*;
+pub use response::*;
+pub use retry::*;
+pub use script::*;
+#[cfg(all(feature = "serial", unix))]
+pub use serial::*;
+pub use tour::*;
+pub use zone::*;
 
+mod address;
+mod alarm;
+mod auxiliary;
+mod batch;
+mod capture;
+mod clock;
+pub mod conformance;
+mod discovery;
+mod duplex;
 mod error;
+pub mod frames;
 mod message;
+mod metrics;
+mod pattern;
 mod port;
+mod preset;
+mod queue;
+mod rate;
+mod response;
+mod retry;
+mod script;
+#[cfg(all(feature = "serial", unix))]
+mod serial;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod text;
+mod tour;
+mod zone;
@@ -1,4 +1,7 @@
+use crate::address::*;
 use crate::error::*;
+use crate::frames;
+use crate::preset::*;
 use std::convert::TryFrom;
 
 const MESSAGE_SIZE: usize = 7;
@@ -7,12 +10,14 @@ const SYNC_BYTE: u8 = 0xFF;
 const SPEED_TURBO_BYTE: u8 = 0xFF;
 const SPEED_MAX_RANGE: f32 = 1.0;
 const SPEED_MIN_RANGE: f32 = 0.0;
+const PAN_POSITION_MAX: u16 = 36000;
 
 /// Speed parameter type for pan and tilt moves.
 ///
 /// The `Range` value must be between 0.0 and 1.0.
 /// `Turbo` works only for pan movements.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Speed {
     Range(f32),
     Turbo,
@@ -20,6 +25,7 @@ pub enum Speed {
 
 /// Argument type for Zoom Speed
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ZoomSpeed {
     Slow = 0,
     Medium = 1,
@@ -29,6 +35,7 @@ pub enum ZoomSpeed {
 
 /// Argument type for FocusSpeed
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FocusSpeed {
     Slow = 0,
     Medium = 1,
@@ -38,6 +45,7 @@ pub enum FocusSpeed {
 
 /// Argument type for Auto/On/Off
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AutoCtrl {
     Auto = 0,
     Off = 1,
@@ -45,6 +53,7 @@ pub enum AutoCtrl {
 
 /// On/Off argument type
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OnOff {
     On,
     Off,
@@ -59,6 +68,7 @@ pub enum OnOff {
 /// Other variants are provided for convenience, but should be used in
 /// accordance to the target device capabilities.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShutterSpeed {
     /// Manual bytes input (byte 5, byte 6)
     Bytes(u8, u8),
@@ -82,6 +92,7 @@ pub enum ShutterSpeed {
 
 /// Argument type for value adjustment functions.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AdjustmentValue {
     New(u16),
     Delta(i16),
@@ -124,6 +135,35 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_for_flags {
+    ($($flags:ident),*) => {$(
+        impl serde::Serialize for $flags {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_u8(self.bits)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $flags {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let bits = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+                $flags::from_bits(bits).ok_or_else(|| {
+                    serde::de::Error::custom(concat!("invalid bits for ", stringify!($flags)))
+                })
+            }
+        }
+    )*};
+}
+
+// The flags are serialized as their bits value
+#[cfg(feature = "serde")]
+impl_serde_for_flags!(Command1, Command2, Direction);
+
 /// Single command message object type.
 ///
 /// There are several way to build a message:
@@ -145,6 +185,20 @@ bitflags! {
 ///
 /// The bytes of a `Message` can be accessed with `Message::as_ref()`.
 ///
+/// Messages have two text representations, which can both be parsed with
+/// `Message::from_str()`:
+///
+///  * A mnemonic format with `Display`, like `addr=10 GOTO_PRESET 5` or
+///    `addr=3 PAN LEFT 0x20 TILT UP 0x10 ZOOM TELE`. Frames with an invalid
+///    sync byte or checksum are displayed as `RAW` followed by their bytes.
+///
+///  * A hexadecimal format with `UpperHex`, like `FF 0A 00 07 00 05 16`.
+///
+/// The constructors of the extended commands and `MessageBuilder` take the
+/// address of the target device as an [Address](enum.Address.html), or as a
+/// `u8` between 1 and 255. The raw constructors `Message::new()` and
+/// `Message::from_bytes()` accept any address byte.
+///
 /// `Message` objects can also be sent to the target device with
 /// [PelcoDPort::send_message()](struct.PelcoDPort.html#method.send_message).
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -169,254 +223,302 @@ impl Message {
     /// );
     /// assert_eq!(&[0xFF, 0x0A, 0x88, 0x90, 0x00, 0x40, 0x62], msg.as_ref());
     /// ```
-    pub fn new(address: u8, cmd1: Command1, cmd2: Command2, data1: u8, data2: u8) -> Message {
-        let mut msg = Message([SYNC_BYTE, address, cmd1.bits, cmd2.bits, data1, data2, 0]);
-        msg.fill_checksum();
-        msg
+    pub const fn new(address: u8, cmd1: Command1, cmd2: Command2, data1: u8, data2: u8) -> Message {
+        Message::from_bytes(address, [cmd1.bits, cmd2.bits, data1, data2])
     }
 
     /// Alternate constructor taking the raw words to insert in the message.
     /// The sync byte and checksum automatically inserted.
-    pub fn from_bytes(address: u8, words: [u8; 4]) -> Message {
-        let mut msg = Message([
-            SYNC_BYTE, address, words[0], words[1], words[2], words[3], 0,
-        ]);
-        msg.fill_checksum();
-        msg
+    pub const fn from_bytes(address: u8, words: [u8; 4]) -> Message {
+        let sum = checksum(&[address, words[0], words[1], words[2], words[3]]);
+        Message([
+            SYNC_BYTE, address, words[0], words[1], words[2], words[3], sum,
+        ])
     }
 
-    fn fill_checksum(&mut self) {
-        self.0[MESSAGE_SIZE - 1] = checksum(&self.0[1..MESSAGE_SIZE]);
+    /// Address of the target device.
+    pub const fn address(&self) -> u8 {
+        self.0[1]
     }
 
     // Extended commands constructors
 
-    /// Set Preset. An error is returned if `preset_id` is 0, but special values
-    /// (like "flip 180") are not checked.
-    pub fn set_preset(address: u8, preset_id: u8) -> Result<Message> {
+    fn extended(address: impl ToAddress, words: [u8; 4]) -> Result<Message> {
+        Ok(Message::from_bytes(address.to_address()?.byte(), words))
+    }
+
+    /// Set Preset. An error is returned if `preset_id` is 0.
+    ///
+    /// Reserved values are *not* checked: `set_preset(address, 33)` silently
+    /// overwrites the "flip 180" function of most devices. Only
+    /// [Message::set_user_preset()](struct.Message.html#method.set_user_preset)
+    /// checks the preset against a [PresetTable](struct.PresetTable.html).
+    pub fn set_preset(address: impl ToAddress, preset_id: u8) -> Result<Message> {
         validate_preset_id(preset_id)?;
-        Ok(Message::from_bytes(address, [0x00, 0x03, 0x00, preset_id]))
+        Message::extended(address, [0x00, 0x03, 0x00, preset_id])
     }
 
     /// Clear Preet. An error is returned if `preset_id` is 0.
-    pub fn clear_preset(address: u8, preset_id: u8) -> Result<Message> {
+    pub fn clear_preset(address: impl ToAddress, preset_id: u8) -> Result<Message> {
         validate_preset_id(preset_id)?;
-        Ok(Message::from_bytes(address, [0x00, 0x05, 0x00, preset_id]))
+        Message::extended(address, [0x00, 0x05, 0x00, preset_id])
     }
 
     /// Call Preet. An error is returned if `preset_id` is 0.
-    pub fn go_to_preset(address: u8, preset_id: u8) -> Result<Message> {
+    pub fn go_to_preset(address: impl ToAddress, preset_id: u8) -> Result<Message> {
         validate_preset_id(preset_id)?;
-        Ok(Message::from_bytes(address, [0x00, 0x07, 0x00, preset_id]))
+        Message::extended(address, [0x00, 0x07, 0x00, preset_id])
+    }
+
+    /// Set Preset, checking that `preset_id` is not reserved for a special
+    /// function by the device model described by `table`.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// let err = Message::set_user_preset(10, 33, &PresetTable::SPECTRA).unwrap_err();
+    /// match err.kind() {
+    ///     ErrorKind::ReservedPreset(SpecialPreset::Flip180) => (),
+    ///     _ => panic!("Preset 33 should be reserved"),
+    /// }
+    /// ```
+    pub fn set_user_preset(
+        address: impl ToAddress,
+        preset_id: u8,
+        table: &PresetTable,
+    ) -> Result<Message> {
+        table.check_user_preset(preset_id)?;
+        Message::set_preset(address, preset_id)
+    }
+
+    /// Call the special preset of the device model described by `table`. An
+    /// error is returned if the model does not support the function.
+    pub fn call_special_preset(
+        address: impl ToAddress,
+        special: SpecialPreset,
+        table: &PresetTable,
+    ) -> Result<Message> {
+        match table.preset_id(special) {
+            Some(preset_id) => Message::go_to_preset(address, preset_id),
+            None => Err(arg_error("Special preset not supported by the device")),
+        }
     }
 
     /// Call the special preset "rotate 180 degrees".
-    pub fn flip_180(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x07, 0x00, 0x21]))
+    pub fn flip_180(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::flip_180(address.to_address()?))
     }
 
     /// Call the special preset "Go To Zero Pan".
-    pub fn go_to_zero_pan(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x07, 0x00, 0x22]))
+    pub fn go_to_zero_pan(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::go_to_zero_pan(address.to_address()?))
     }
 
     /// Set Auxiliary. No particular check is done on the arguments.
-    pub fn set_auxiliary(address: u8, sub_opcode: u8, aux_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(
-            address,
-            [sub_opcode, 0x09, 0x00, aux_id],
+    pub fn set_auxiliary(address: impl ToAddress, sub_opcode: u8, aux_id: u8) -> Result<Message> {
+        Ok(frames::set_auxiliary(
+            address.to_address()?,
+            sub_opcode,
+            aux_id,
         ))
     }
 
     /// Clear Auxiliary. No particular check is done on the arguments.
-    pub fn clear_auxiliary(address: u8, sub_opcode: u8, aux_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(
-            address,
-            [sub_opcode, 0x0B, 0x00, aux_id],
+    pub fn clear_auxiliary(address: impl ToAddress, sub_opcode: u8, aux_id: u8) -> Result<Message> {
+        Ok(frames::clear_auxiliary(
+            address.to_address()?,
+            sub_opcode,
+            aux_id,
         ))
     }
 
     /// Reset.
-    pub fn remote_reset(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x0F, 0x00, 0x00]))
+    pub fn remote_reset(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::remote_reset(address.to_address()?))
     }
 
     /// Set Zone Start.
-    pub fn set_zone_start(address: u8, zone_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x11, 0x00, zone_id]))
+    pub fn set_zone_start(address: impl ToAddress, zone_id: u8) -> Result<Message> {
+        Ok(frames::set_zone_start(address.to_address()?, zone_id))
     }
 
     /// Set Zone End.
-    pub fn set_zone_end(address: u8, zone_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x13, 0x00, zone_id]))
+    pub fn set_zone_end(address: impl ToAddress, zone_id: u8) -> Result<Message> {
+        Ok(frames::set_zone_end(address.to_address()?, zone_id))
     }
 
     /// Write Character To Screen.
-    pub fn write_char_to_screen(address: u8, column: u8, character: char) -> Result<Message> {
+    pub fn write_char_to_screen(
+        address: impl ToAddress,
+        column: u8,
+        character: char,
+    ) -> Result<Message> {
         if character.is_ascii() {
             let ascii = character as u8;
-            Ok(Message::from_bytes(address, [0x00, 0x15, column, ascii]))
+            Message::extended(address, [0x00, 0x15, column, ascii])
         } else {
             Err(arg_error("Invalid ASCII character"))
         }
     }
 
     /// Clear Screen.
-    pub fn clear_screen(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x17, 0x00, 0x00]))
+    pub fn clear_screen(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::clear_screen(address.to_address()?))
     }
 
     /// Alarm Acknowledge.
-    pub fn alarm_acknowledge(address: u8, alarm_no: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x19, 0x00, alarm_no]))
+    pub fn alarm_acknowledge(address: impl ToAddress, alarm_no: u8) -> Result<Message> {
+        Ok(frames::alarm_acknowledge(address.to_address()?, alarm_no))
     }
 
     /// Zone Scan On.
-    pub fn zone_scan_on(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x1B, 0x00, 0x00]))
+    pub fn zone_scan_on(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::zone_scan_on(address.to_address()?))
     }
 
     /// Zone Scan Off.
-    pub fn zone_scan_off(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x1D, 0x00, 0x00]))
+    pub fn zone_scan_off(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::zone_scan_off(address.to_address()?))
     }
 
     /// Record Pattern Start.
-    pub fn set_pattern_start(address: u8, pattern_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x1F, 0x00, pattern_id]))
+    pub fn set_pattern_start(address: impl ToAddress, pattern_id: u8) -> Result<Message> {
+        Ok(frames::set_pattern_start(address.to_address()?, pattern_id))
     }
 
     /// Record Pattern End.
-    pub fn set_pattern_stop(address: u8, pattern_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x21, 0x00, pattern_id]))
+    pub fn set_pattern_stop(address: impl ToAddress, pattern_id: u8) -> Result<Message> {
+        Ok(frames::set_pattern_stop(address.to_address()?, pattern_id))
     }
 
     /// Run Pattern.
-    pub fn run_pattern(address: u8, pattern_id: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x23, 0x00, pattern_id]))
+    pub fn run_pattern(address: impl ToAddress, pattern_id: u8) -> Result<Message> {
+        Ok(frames::run_pattern(address.to_address()?, pattern_id))
     }
 
     /// Set Zoom Speed.
-    pub fn set_zoom_speed(address: u8, speed: ZoomSpeed) -> Result<Message> {
-        Ok(Message::from_bytes(
-            address,
-            [0x00, 0x25, 0x00, speed as u8],
-        ))
+    pub fn set_zoom_speed(address: impl ToAddress, speed: ZoomSpeed) -> Result<Message> {
+        Ok(frames::set_zoom_speed(address.to_address()?, speed))
     }
 
     /// Set Focus Speed.
-    pub fn set_focus_speed(address: u8, speed: FocusSpeed) -> Result<Message> {
-        Ok(Message::from_bytes(
-            address,
-            [0x00, 0x27, 0x00, speed as u8],
-        ))
+    pub fn set_focus_speed(address: impl ToAddress, speed: FocusSpeed) -> Result<Message> {
+        Ok(frames::set_focus_speed(address.to_address()?, speed))
     }
 
     /// Reset Camera to Defaults.
-    pub fn reset_camera_to_defaults(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x29, 0x00, 0x00]))
+    pub fn reset_camera_to_defaults(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::reset_camera_to_defaults(address.to_address()?))
     }
 
     /// Auto Focus.
-    pub fn auto_focus(address: u8, ctrl: AutoCtrl) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x2B, 0x00, ctrl as u8]))
+    pub fn auto_focus(address: impl ToAddress, ctrl: AutoCtrl) -> Result<Message> {
+        Ok(frames::auto_focus(address.to_address()?, ctrl))
     }
 
     /// Auto Iris.
-    pub fn auto_iris(address: u8, cmd: AutoCtrl) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x2D, 0x00, cmd as u8]))
+    pub fn auto_iris(address: impl ToAddress, cmd: AutoCtrl) -> Result<Message> {
+        Ok(frames::auto_iris(address.to_address()?, cmd))
     }
 
     /// AGC.
-    pub fn agc(address: u8, cmd: AutoCtrl) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x2F, 0x00, cmd as u8]))
+    pub fn agc(address: impl ToAddress, cmd: AutoCtrl) -> Result<Message> {
+        Ok(frames::agc(address.to_address()?, cmd))
     }
 
     /// Backlight Compensation.
-    pub fn backlight_compensation(address: u8, ctrl: OnOff) -> Result<Message> {
-        let ctrl = match ctrl {
-            OnOff::On => 2,
-            OnOff::Off => 1,
-            OnOff::Value(x) => x,
-        };
-        Ok(Message::from_bytes(address, [0x00, 0x31, 0x00, ctrl]))
+    pub fn backlight_compensation(address: impl ToAddress, ctrl: OnOff) -> Result<Message> {
+        Ok(frames::backlight_compensation(address.to_address()?, ctrl))
     }
 
     /// Auto White Balance.
-    pub fn auto_white_balance(address: u8, ctrl: OnOff) -> Result<Message> {
-        let ctrl = match ctrl {
-            OnOff::On => 1,
-            OnOff::Off => 2,
-            OnOff::Value(x) => x,
-        };
-        Ok(Message::from_bytes(address, [0x00, 0x33, 0x00, ctrl]))
+    pub fn auto_white_balance(address: impl ToAddress, ctrl: OnOff) -> Result<Message> {
+        Ok(frames::auto_white_balance(address.to_address()?, ctrl))
     }
 
     /// Enable Device Phase Delay Mode.
-    pub fn enable_device_phase_delay_mode(address: u8) -> Result<Message> {
-        Ok(Message::from_bytes(address, [0x00, 0x35, 0x00, 0x00]))
+    pub fn enable_device_phase_delay_mode(address: impl ToAddress) -> Result<Message> {
+        Ok(frames::enable_device_phase_delay_mode(
+            address.to_address()?,
+        ))
     }
 
     /// Set Shutter Speed.
-    pub fn set_shutter_speed(address: u8, ctrl: ShutterSpeed) -> Result<Message> {
-        let data: [u8; 2] = match ctrl {
-            ShutterSpeed::Bytes(d1, d2) => [d1, d2],
-            ShutterSpeed::DefaultValue => [0, 0],
-            ShutterSpeed::Increment => [0, 1],
-            ShutterSpeed::Decrement => [0, 2],
-            ShutterSpeed::PAL => [0, 50],
-            ShutterSpeed::NTSC => [0, 60],
-            ShutterSpeed::Value(secs) => secs.to_be_bytes(),
-            ShutterSpeed::AutoShutter => [0, 0],
-            ShutterSpeed::Index(idx) => [0, idx],
-        };
-        Ok(Message::from_bytes(address, [0x00, 0x37, data[0], data[1]]))
-    }
-
-    fn adjust(address: u8, opcode: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        let (cmd, data): (u8, [u8; 2]) = match ctrl {
-            AdjustmentValue::New(value) => (0, value.to_be_bytes()),
-            AdjustmentValue::Delta(value) => (1, value.to_be_bytes()),
-        };
-        Ok(Message::from_bytes(
-            address,
-            [cmd, opcode, data[0], data[1]],
-        ))
+    pub fn set_shutter_speed(address: impl ToAddress, ctrl: ShutterSpeed) -> Result<Message> {
+        Ok(frames::set_shutter_speed(address.to_address()?, ctrl))
     }
 
     /// Adjust Line Lock Phase Delay.
-    pub fn adjust_line_lock_phase_delay(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x39, ctrl)
+    pub fn adjust_line_lock_phase_delay(
+        address: impl ToAddress,
+        ctrl: AdjustmentValue,
+    ) -> Result<Message> {
+        Ok(frames::adjust_line_lock_phase_delay(
+            address.to_address()?,
+            ctrl,
+        ))
     }
 
     /// Adjust White Balance (R-B)
-    pub fn adjust_white_balance_rb(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x3B, ctrl)
+    pub fn adjust_white_balance_rb(
+        address: impl ToAddress,
+        ctrl: AdjustmentValue,
+    ) -> Result<Message> {
+        Ok(frames::adjust_white_balance_rb(address.to_address()?, ctrl))
     }
 
     /// Adjust White Balance (M-G)
-    pub fn adjust_white_balance_mg(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x3D, ctrl)
+    pub fn adjust_white_balance_mg(
+        address: impl ToAddress,
+        ctrl: AdjustmentValue,
+    ) -> Result<Message> {
+        Ok(frames::adjust_white_balance_mg(address.to_address()?, ctrl))
     }
 
     /// Adjust Gain.
-    pub fn adjust_gain(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x3F, ctrl)
+    pub fn adjust_gain(address: impl ToAddress, ctrl: AdjustmentValue) -> Result<Message> {
+        Ok(frames::adjust_gain(address.to_address()?, ctrl))
     }
 
     /// Adjust Auto-Iris Level
-    pub fn adjust_auto_iris_level(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x41, ctrl)
+    pub fn adjust_auto_iris_level(
+        address: impl ToAddress,
+        ctrl: AdjustmentValue,
+    ) -> Result<Message> {
+        Ok(frames::adjust_auto_iris_level(address.to_address()?, ctrl))
     }
 
     /// Adjust Auto-Iris Peak Value.
-    pub fn adjust_auto_iris_peak(address: u8, ctrl: AdjustmentValue) -> Result<Message> {
-        Message::adjust(address, 0x43, ctrl)
+    pub fn adjust_auto_iris_peak(
+        address: impl ToAddress,
+        ctrl: AdjustmentValue,
+    ) -> Result<Message> {
+        Ok(frames::adjust_auto_iris_peak(address.to_address()?, ctrl))
     }
 
-    /// Query.
+    /// Query, sent to the broadcast address.
+    ///
+    /// The query is meant for a single device connected to the line, which
+    /// answers with its part number whatever its own address. It must not be
+    /// used on a bus with several devices, since all of them would answer at
+    /// the same time.
     pub fn query() -> Result<Message> {
-        Ok(Message::from_bytes(0, [0x00, 0x45, 0x00, 0x00]))
+        Ok(frames::query(Address::Broadcast))
+    }
+
+    // Query addressed to a single device
+    pub(crate) fn query_to(address: u8) -> Message {
+        Message::from_bytes(address, [0x00, 0x45, 0x00, 0x00])
+    }
+
+    /// Set Pan Position, in hundredths of degree. An error is returned if
+    /// `position` is not below 36000.
+    pub fn set_pan_position(address: impl ToAddress, position: u16) -> Result<Message> {
+        if position < PAN_POSITION_MAX {
+            let data = position.to_be_bytes();
+            Message::extended(address, [0x00, 0x4B, data[0], data[1]])
+        } else {
+            Err(arg_error("Invalid pan position"))
+        }
     }
 }
 
@@ -442,7 +544,7 @@ impl From<[u8; MESSAGE_SIZE]> for Message {
 impl From<MessageBuilder> for Message {
     fn from(draft: MessageBuilder) -> Self {
         Message::new(
-            draft.address,
+            draft.address.byte(),
             draft.cmd1,
             draft.cmd2,
             draft.data1,
@@ -492,7 +594,7 @@ fn validate_preset_id(idx: u8) -> Result<()> {
 /// # use pelcodrs::*;
 /// # use pelcodrs::*;
 /// # fn example() -> Result<()> {
-/// let msg = MessageBuilder::new(10)
+/// let msg = MessageBuilder::new(10)?
 ///     .camera_on()
 ///     .focus_far()
 ///     .direction(Direction::DOWN)
@@ -505,8 +607,9 @@ fn validate_preset_id(idx: u8) -> Result<()> {
 ///
 /// ```
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageBuilder {
-    address: u8,
+    address: Address,
     cmd1: Command1,
     cmd2: Command2,
     data1: u8,
@@ -514,14 +617,16 @@ pub struct MessageBuilder {
 }
 
 impl MessageBuilder {
-    pub fn new(address: u8) -> MessageBuilder {
-        MessageBuilder {
-            address,
+    /// New builder of a message sent to `address`. An error is returned if
+    /// the address is invalid.
+    pub fn new(address: impl ToAddress) -> Result<MessageBuilder> {
+        Ok(MessageBuilder {
+            address: address.to_address()?,
             cmd1: Command1::empty(),
             cmd2: Command2::empty(),
             data1: 0,
             data2: 0,
-        }
+        })
     }
 
     pub fn direction(&mut self, direction: Direction) -> &mut Self {
@@ -623,11 +728,17 @@ impl MessageBuilder {
 }
 
 /// Checksum algorithm used by Pelco D.
-pub fn checksum(data: &[u8]) -> u8 {
-    let s: u32 = data.iter().map(|&b| u32::from(b)).sum();
-    (s & 0xff) as u8
+pub const fn checksum(data: &[u8]) -> u8 {
+    let mut sum = 0u8;
+    let mut i = 0;
+    while i < data.len() {
+        sum = sum.wrapping_add(data[i]);
+        i += 1;
+    }
+    sum
 }
 
+#[allow(clippy::manual_clamp)]
 fn speed_to_byte(speed: Speed) -> u8 {
     match speed {
         Speed::Range(range) => {
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Traffic counters of a single device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressMetrics {
+    pub frames_sent: u64,
+    pub bytes_written: u64,
+    pub replies_received: u64,
+    /// Transactions which received no reply in time.
+    pub timeouts: u64,
+    /// Frames sent again after a timeout or an invalid reply.
+    pub retries: u64,
+    /// Time of the last frame sent, according to the port clock.
+    pub last_sent: Option<Duration>,
+    /// Time of the last reply received, according to the port clock.
+    pub last_seen: Option<Duration>,
+    /// Frames sent per command name, like `GOTO_PRESET` or `STOP`.
+    pub commands: BTreeMap<&'static str, u64>,
+}
+
+/// Traffic counters of a [PelcoDPort](struct.PelcoDPort.html).
+///
+/// The port totals include the bytes which were not part of a frame, like
+/// noise on the line.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// port.send_message(Message::go_to_preset(10, 5)?)?;
+///
+/// let metrics = port.metrics();
+/// assert_eq!(1, metrics.frames_sent);
+/// assert_eq!(Some(&1), metrics.addresses[&10].commands.get("GOTO_PRESET"));
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortMetrics {
+    /// Time of the snapshot, according to the port clock.
+    pub timestamp: Duration,
+    pub frames_sent: u64,
+    pub bytes_written: u64,
+    pub bytes_read: u64,
+    pub replies_received: u64,
+    /// Received frames discarded because of a wrong checksum.
+    pub checksum_failures: u64,
+    pub timeouts: u64,
+    pub retries: u64,
+    /// Counters of each device address.
+    pub addresses: BTreeMap<u8, AddressMetrics>,
+}
+
+impl PortMetrics {
+    /// Counters of the device at `address`, created if needed.
+    pub(crate) fn address_mut(&mut self, address: u8) -> &mut AddressMetrics {
+        self.addresses.entry(address).or_default()
+    }
+
+    /// Metrics in the Prometheus text exposition format, with `port` as
+    /// value of the `port` label.
+    ///
+    /// The time since the last reply of each device is exported as
+    /// `pelcod_last_reply_age_seconds`, to detect devices which stopped
+    /// answering.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// # fn example() -> Result<()> {
+    /// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+    /// port.send_message(Message::go_to_preset(10, 5)?)?;
+    ///
+    /// let text = port.metrics().to_prometheus("/dev/ttyS0");
+    /// assert!(text.contains("pelcod_frames_sent_total{port=\"/dev/ttyS0\",address=\"10\"} 1\n"));
+    /// # Ok(())}
+    /// # example().unwrap();
+    /// ```
+    pub fn to_prometheus(&self, port: &str) -> String {
+        let port = escape_label(port);
+        let mut out = String::new();
+
+        let port_counters: [(&str, &str, u64); 7] = [
+            ("frames_sent", "Frames sent", self.frames_sent),
+            ("bytes_written", "Bytes written", self.bytes_written),
+            ("bytes_read", "Bytes read", self.bytes_read),
+            (
+                "replies_received",
+                "Replies received",
+                self.replies_received,
+            ),
+            (
+                "checksum_failures",
+                "Received frames with a wrong checksum",
+                self.checksum_failures,
+            ),
+            ("timeouts", "Transactions without reply", self.timeouts),
+            ("retries", "Frames sent again", self.retries),
+        ];
+        for (name, help, value) in port_counters.iter() {
+            let _ = writeln!(out, "# HELP pelcod_port_{}_total {}.", name, help);
+            let _ = writeln!(out, "# TYPE pelcod_port_{}_total counter", name);
+            let _ = writeln!(
+                out,
+                "pelcod_port_{}_total{{port=\"{}\"}} {}",
+                name, port, value
+            );
+        }
+
+        type Getter = fn(&AddressMetrics) -> u64;
+        let address_counters: [(&str, &str, Getter); 5] = [
+            ("frames_sent", "Frames sent to the device", |m| {
+                m.frames_sent
+            }),
+            ("bytes_written", "Bytes sent to the device", |m| {
+                m.bytes_written
+            }),
+            ("replies_received", "Replies of the device", |m| {
+                m.replies_received
+            }),
+            ("timeouts", "Transactions without reply", |m| m.timeouts),
+            ("retries", "Frames sent again", |m| m.retries),
+        ];
+        for (name, help, get) in address_counters.iter() {
+            let _ = writeln!(out, "# HELP pelcod_{}_total {}.", name, help);
+            let _ = writeln!(out, "# TYPE pelcod_{}_total counter", name);
+            for (address, metrics) in self.addresses.iter() {
+                let _ = writeln!(
+                    out,
+                    "pelcod_{}_total{{port=\"{}\",address=\"{}\"}} {}",
+                    name,
+                    port,
+                    address,
+                    get(metrics)
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP pelcod_commands_sent_total Frames sent to the device per command."
+        );
+        let _ = writeln!(out, "# TYPE pelcod_commands_sent_total counter");
+        for (address, metrics) in self.addresses.iter() {
+            for (command, count) in metrics.commands.iter() {
+                let _ = writeln!(
+                    out,
+                    "pelcod_commands_sent_total{{port=\"{}\",address=\"{}\",command=\"{}\"}} {}",
+                    port, address, command, count
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP pelcod_last_reply_age_seconds Time since the last reply of the device."
+        );
+        let _ = writeln!(out, "# TYPE pelcod_last_reply_age_seconds gauge");
+        for (address, metrics) in self.addresses.iter() {
+            if let Some(last_seen) = metrics.last_seen {
+                let _ = writeln!(
+                    out,
+                    "pelcod_last_reply_age_seconds{{port=\"{}\",address=\"{}\"}} {}",
+                    port,
+                    address,
+                    self.timestamp.saturating_sub(last_seen).as_secs_f64()
+                );
+            }
+        }
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
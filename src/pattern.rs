@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::error::*;
+use crate::message::*;
+use crate::port::PelcoDPort;
+
+/// Message of a [Pattern](struct.Pattern.html), sent at `offset` from the
+/// start of the pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternStep {
+    pub offset: Duration,
+    pub message: Message,
+}
+
+impl PatternStep {
+    pub fn new(offset: Duration, message: Message) -> PatternStep {
+        PatternStep { offset, message }
+    }
+}
+
+/// Timed sequence of motion commands forming a pattern.
+///
+/// A pattern can be recorded in the device memory with a
+/// [PatternRecorder](struct.PatternRecorder.html), or replayed locally with
+/// [Pattern::replay()](struct.Pattern.html#method.replay) for devices without
+/// pattern memory.
+///
+/// The camera is stopped after the last step, which therefore gets no run
+/// time. A pattern should end with a stop step at the offset where the motion
+/// ends, like the patterns recorded live; no other stop is sent then.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    address: u8,
+    id: u8,
+    steps: Vec<PatternStep>,
+}
+
+impl Pattern {
+    /// New pattern `id` for the device at `address`. An error is returned if
+    /// the steps are not sorted by offset, or if a step is sent to another
+    /// address.
+    pub fn new(address: u8, id: u8, steps: Vec<PatternStep>) -> Result<Pattern> {
+        if steps.windows(2).any(|w| w[0].offset > w[1].offset) {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                "Pattern steps must be sorted by offset",
+            ));
+        }
+        if steps.iter().any(|step| step.message.address() != address) {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                "Pattern steps must be sent to the pattern address",
+            ));
+        }
+        Ok(Pattern { address, id, steps })
+    }
+
+    /// Address of the device.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Pattern number.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Steps of the pattern.
+    pub fn steps(&self) -> &[PatternStep] {
+        &self.steps
+    }
+
+    /// Duration of the pattern, which is the offset of the last step.
+    pub fn duration(&self) -> Duration {
+        self.steps
+            .last()
+            .map(|step| step.offset)
+            .unwrap_or_default()
+    }
+
+    /// Send the steps with their original timing, using the clock of the
+    /// port, then stop the camera.
+    ///
+    /// The camera is stopped even if sending a step fails, and the first
+    /// error is returned.
+    pub fn replay<T: Read + Write>(&self, port: &mut PelcoDPort<T>) -> Result<()> {
+        let stop = stop_message(self.address)?;
+        let sent = self.send_steps(port);
+        // Do not leave the camera moving after an error
+        let stopped = match sent {
+            Ok(()) if self.ends_with(stop) => Ok(()),
+            _ => port.send_message(stop),
+        };
+        sent.and(stopped)
+    }
+
+    fn send_steps<T: Read + Write>(&self, port: &mut PelcoDPort<T>) -> Result<()> {
+        let start = port.clock().now();
+        for step in self.steps.iter() {
+            let wait = (start + step.offset).saturating_sub(port.clock().now());
+            port.clock().sleep(wait);
+            port.send_message(step.message)?;
+        }
+        Ok(())
+    }
+
+    fn ends_with(&self, message: Message) -> bool {
+        self.steps.last().map(|step| step.message) == Some(message)
+    }
+}
+
+/// How a pattern is run by
+/// [PatternRecorder::run()](struct.PatternRecorder.html#method.run).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// The device runs the pattern from its memory.
+    Device,
+    /// The locally stored pattern is replayed by sending its steps.
+    Local,
+}
+
+#[derive(Debug, Clone)]
+struct LiveRecording {
+    address: u8,
+    id: u8,
+    start: Duration,
+    steps: Vec<PatternStep>,
+}
+
+/// Records patterns in the devices, and keeps a local copy of them.
+///
+/// A pattern is recorded either from a predefined
+/// [Pattern](struct.Pattern.html) with
+/// [PatternRecorder::record()](struct.PatternRecorder.html#method.record), or
+/// live by sending the messages through the recorder between
+/// [PatternRecorder::start()](struct.PatternRecorder.html#method.start) and
+/// [PatternRecorder::stop()](struct.PatternRecorder.html#method.stop).
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// # fn example() -> Result<()> {
+/// let clock = ManualClock::new();
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// port.set_clock(clock.clone());
+/// let mut recorder = PatternRecorder::new();
+///
+/// recorder.start(&mut port, 10, 1)?;
+/// let left = MessageBuilder::new(10)?.left().pan(Speed::Range(0.5)).finalize()?;
+/// recorder.send(&mut port, left)?;
+/// clock.advance(Duration::from_secs(3));
+/// let pattern = recorder.stop(&mut port)?;
+///
+/// assert_eq!(Duration::from_secs(3), pattern.duration());
+/// assert!(recorder.pattern(10, 1).is_some());
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PatternRecorder {
+    patterns: BTreeMap<(u8, u8), Pattern>,
+    live: Option<LiveRecording>,
+}
+
+impl PatternRecorder {
+    pub fn new() -> PatternRecorder {
+        PatternRecorder::default()
+    }
+
+    /// Record `pattern` in the device: start the recording, send the steps
+    /// with their timing, stop the camera and end the recording. The pattern
+    /// is then stored locally.
+    ///
+    /// If sending a step fails, the camera is still stopped and the
+    /// recording ended before the error is returned, and the pattern is not
+    /// stored.
+    pub fn record<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        pattern: Pattern,
+    ) -> Result<()> {
+        self.ensure_not_recording()?;
+        let end = Message::set_pattern_stop(pattern.address, pattern.id)?;
+        port.send_message(Message::set_pattern_start(pattern.address, pattern.id)?)?;
+        // Do not leave the device moving in recording mode after an error
+        let replayed = pattern.replay(port);
+        let ended = port.send_message(end);
+        replayed.and(ended)?;
+        self.insert(pattern);
+        Ok(())
+    }
+
+    /// Start recording live the pattern `id` of the device at `address`.
+    pub fn start<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        id: u8,
+    ) -> Result<()> {
+        self.ensure_not_recording()?;
+        port.send_message(Message::set_pattern_start(address, id)?)?;
+        self.live = Some(LiveRecording {
+            address,
+            id,
+            start: port.clock().now(),
+            steps: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Send a message, which is added to the pattern being recorded live if
+    /// it is for the same device.
+    pub fn send<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        message: Message,
+    ) -> Result<()> {
+        port.send_message(message)?;
+        if let Some(live) = self.live.as_mut() {
+            if message.address() == live.address {
+                let offset = port.clock().now().saturating_sub(live.start);
+                live.steps.push(PatternStep::new(offset, message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop the camera and end the live recording. The recorded pattern,
+    /// ending with the stop, is stored locally and returned.
+    ///
+    /// If a message cannot be sent, the recording stays in progress and this
+    /// method can be called again.
+    pub fn stop<T: Read + Write>(&mut self, port: &mut PelcoDPort<T>) -> Result<&Pattern> {
+        let (address, id) = match self.live.as_ref() {
+            Some(live) => (live.address, live.id),
+            None => return Err(Error::new(ErrorKind::InvalidValue, "No pattern recording")),
+        };
+        let stop = stop_message(address)?;
+        port.send_message(stop)?;
+        port.send_message(Message::set_pattern_stop(address, id)?)?;
+        let mut live = self.live.take().unwrap();
+        live.steps.push(PatternStep::new(
+            port.clock().now().saturating_sub(live.start),
+            stop,
+        ));
+
+        let key = (live.address, live.id);
+        self.insert(Pattern::new(live.address, live.id, live.steps)?);
+        Ok(&self.patterns[&key])
+    }
+
+    /// Whether a live recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.live.is_some()
+    }
+
+    /// Store a pattern locally, replacing the previous one with the same
+    /// address and number.
+    pub fn insert(&mut self, pattern: Pattern) {
+        self.patterns.insert((pattern.address, pattern.id), pattern);
+    }
+
+    /// Locally stored pattern `id` of the device at `address`.
+    pub fn pattern(&self, address: u8, id: u8) -> Option<&Pattern> {
+        self.patterns.get(&(address, id))
+    }
+
+    /// All the locally stored patterns.
+    pub fn patterns(&self) -> impl Iterator<Item = &Pattern> {
+        self.patterns.values()
+    }
+
+    /// Run the pattern `id` of the device at `address`. With
+    /// `Playback::Local`, an error is returned if the pattern is not stored
+    /// locally.
+    pub fn run<T: Read + Write>(
+        &self,
+        port: &mut PelcoDPort<T>,
+        address: u8,
+        id: u8,
+        playback: Playback,
+    ) -> Result<()> {
+        match playback {
+            Playback::Device => port.send_message(Message::run_pattern(address, id)?),
+            Playback::Local => match self.pattern(address, id) {
+                Some(pattern) => pattern.replay(port),
+                None => Err(Error::new(ErrorKind::InvalidValue, "Unknown pattern")),
+            },
+        }
+    }
+
+    fn ensure_not_recording(&self) -> Result<()> {
+        if self.live.is_some() {
+            Err(Error::new(
+                ErrorKind::InvalidValue,
+                "A pattern is already being recorded",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn stop_message(address: u8) -> Result<Message> {
+    MessageBuilder::new(address)?.stop().finalize()
+}
@@ -1,7 +1,22 @@
 use std::io::{Read, Write};
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::address::Address;
+use crate::clock::*;
+use crate::duplex::*;
+use crate::error::*;
 use crate::message::*;
-use crate::Result;
+use crate::metrics::PortMetrics;
+use crate::rate::*;
+use crate::response::*;
+use crate::retry::RetryPolicy;
+use crate::text::command_name;
+#[cfg(feature = "tracing")]
+use crate::text::hex;
+
+// Wait between two reads while waiting for a response
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
 
 /// Interface for communicating with a device using Pelco D protocol.
 ///
@@ -21,33 +36,423 @@ use crate::Result;
 /// pelcod_dev.send_message(Message::flip_180(10)?)?;
 /// # Ok(())}
 /// ```
+///
+/// With the `serial` feature, `PelcoDPort::open()` opens and configures the
+/// serial port.
 #[derive(Debug)]
-pub struct PelcoDPort<T: Read + Write>(T);
+pub struct PelcoDPort<T: Read + Write> {
+    inner: T,
+    parser: ResponseParser,
+    clock: Arc<dyn Clock>,
+    metrics: PortMetrics,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    half_duplex: Option<HalfDuplexLine<T>>,
+    broadcast_address: Address,
+    // Received bytes to return before reading the transport
+    received: Vec<u8>,
+}
 
 impl<T: Read + Write> PelcoDPort<T> {
     pub fn new(ser: T) -> PelcoDPort<T> {
-        PelcoDPort(ser)
+        PelcoDPort {
+            inner: ser,
+            parser: ResponseParser::new(ResponseKind::General),
+            clock: Arc::new(SystemClock::new()),
+            metrics: PortMetrics::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            half_duplex: None,
+            broadcast_address: Address::Broadcast,
+            received: Vec::new(),
+        }
+    }
+
+    /// Use the port in half-duplex mode: the transmitter is enabled with
+    /// `control` during each write, and the echo of the written bytes is
+    /// discarded if configured.
+    ///
+    /// An error of `control` while enabling the receiver after a write is
+    /// returned by the next write, so that the written bytes are still
+    /// accounted for.
+    ///
+    /// ```rust, ignore
+    /// let mut port = PelcoDPort::new(serialport::open("/dev/ttyUSB0")?);
+    /// port.set_half_duplex(
+    ///     RtsControl::default(),
+    ///     HalfDuplex {
+    ///         rx_delay: Duration::from_millis(30),
+    ///         discard_echo: true,
+    ///         ..HalfDuplex::default()
+    ///     },
+    /// );
+    /// ```
+    pub fn set_half_duplex<D: DirectionControl<T> + Send + 'static>(
+        &mut self,
+        control: D,
+        settings: HalfDuplex,
+    ) -> &mut Self {
+        self.half_duplex = Some(HalfDuplexLine::new(control, settings));
+        self
+    }
+
+    /// Use the port in full-duplex mode, which is the default.
+    pub fn set_full_duplex(&mut self) -> &mut Self {
+        self.half_duplex = None;
+        self
+    }
+
+    pub fn is_half_duplex(&self) -> bool {
+        self.half_duplex.is_some()
+    }
+
+    /// Default retry policy of the transactions.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Limits on the sent frames.
+    /// [send_message()](struct.PelcoDPort.html#method.send_message) waits
+    /// as needed to respect them.
+    pub fn set_rate_limit(&mut self, limit: RateLimit) -> &mut Self {
+        self.rate_limiter.set_limit(limit);
+        self
+    }
+
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limiter.limit()
+    }
+
+    /// Time to wait before a frame can be sent to `address` without
+    /// exceeding the rate limit.
+    pub fn send_delay(&self, address: u8) -> Duration {
+        self.rate_limiter.delay(address, self.clock.now())
+    }
+
+    /// Clock used for the timestamps and the timings of the port. It is a
+    /// [SystemClock](struct.SystemClock.html) by default.
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) -> &mut Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Clock of the port, for timing operations consistently with it.
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    /// Address used by
+    /// [broadcast()](struct.PelcoDPort.html#method.broadcast), which is
+    /// `Address::Broadcast` by default. Installations using 0xFF as a
+    /// broadcast address can set it instead.
+    pub fn set_broadcast_address(&mut self, address: Address) -> &mut Self {
+        self.broadcast_address = address;
+        self
+    }
+
+    pub fn broadcast_address(&self) -> Address {
+        self.broadcast_address
+    }
+
+    /// Snapshot of the traffic counters.
+    pub fn metrics(&self) -> PortMetrics {
+        let mut metrics = self.metrics.clone();
+        metrics.timestamp = self.clock.now();
+        metrics
+    }
+
+    /// Reset the traffic counters.
+    pub fn reset_metrics(&mut self) {
+        self.metrics = PortMetrics::default();
+    }
+
+    /// Underlying `Read + Write` object.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Mutable underlying `Read + Write` object. Reading or writing directly
+    /// bypasses the features of the port.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Return the underlying `Read + Write` object.
+    pub fn into_inner(self) -> T {
+        self.inner
     }
 }
 
 impl<T: Read + Write> Read for PelcoDPort<T> {
     fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
-        self.0.read(buf)
+        let result = if self.received.is_empty() {
+            self.inner.read(buf)
+        } else {
+            let count = self.received.len().min(buf.len());
+            buf[..count].copy_from_slice(&self.received[..count]);
+            self.received.drain(..count);
+            Ok(count)
+        };
+        if let Ok(count) = result {
+            self.metrics.bytes_read += count as u64;
+        }
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(0) => (),
+            Ok(count) => tracing::trace!(bytes = %hex(&buf[..*count]), "bytes read"),
+            Err(e) if is_no_data(e) => (),
+            Err(e) => tracing::error!(error = %e, "read failed"),
+        }
+        result
     }
 }
 
 impl<T: Read + Write> Write for PelcoDPort<T> {
     fn write(&mut self, buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
-        self.0.write(buf)
+        let result = match self.half_duplex.as_mut() {
+            Some(line) => line.write(&mut self.inner, &*self.clock, buf, &mut self.received),
+            None => self.inner.write(buf),
+        };
+        if let Ok(count) = result {
+            self.metrics.bytes_written += count as u64;
+        }
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(count) => tracing::trace!(bytes = %hex(&buf[..*count]), "bytes written"),
+            Err(e) => tracing::error!(error = %e, "write failed"),
+        }
+        result
     }
 
     fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
-        self.0.flush()
+        self.inner.flush()
     }
 }
 
 impl<T: Read + Write> PelcoDPort<T> {
+    /// Send a message, after waiting as needed to respect the rate limit.
     pub fn send_message(&mut self, message: Message) -> Result<()> {
-        Ok(self.write_all(message.as_ref())?)
+        let delay = self.send_delay(message.address());
+        if delay > Duration::from_secs(0) {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(delay_us = delay.as_micros() as u64, "rate limited");
+            self.clock.sleep(delay);
+        }
+
+        #[cfg(feature = "tracing")]
+        let start = self.clock.now();
+
+        let result = self.write_all(message.as_ref());
+        if result.is_ok() {
+            self.record_sent(&message);
+        }
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(()) => tracing::debug!(
+                address = message.address(),
+                command = %message,
+                bytes = format_args!("{:X}", message),
+                elapsed_us = self.clock.now().saturating_sub(start).as_micros() as u64,
+                "frame sent"
+            ),
+            Err(e) => tracing::warn!(
+                address = message.address(),
+                command = %message,
+                error = %e,
+                "frame not sent"
+            ),
+        }
+
+        Ok(result?)
+    }
+
+    // Update the rate limiter and the metrics after writing a message
+    pub(crate) fn record_sent(&mut self, message: &Message) {
+        let now = self.clock.now();
+        self.rate_limiter.record(message.address(), now);
+        self.metrics.frames_sent += 1;
+        let metrics = self.metrics.address_mut(message.address());
+        metrics.frames_sent += 1;
+        metrics.bytes_written += message.as_ref().len() as u64;
+        metrics.last_sent = Some(now);
+        *metrics.commands.entry(command_name(message)).or_default() += 1;
+    }
+
+    /// Receive a response of the given kind.
+    ///
+    /// The bytes available from the port are read without blocking beyond
+    /// the port own read timeout. `None` is returned if no complete response
+    /// was received yet, in which case the received bytes are kept for the
+    /// next call.
+    ///
+    /// A response with a wrong checksum is discarded and returned as an error
+    /// of kind `ErrorKind::ChecksumMismatch`.
+    pub fn receive_response(&mut self, kind: ResponseKind) -> Result<Option<Response>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("receive_response", kind = ?kind).entered();
+
+        let result = self.parse_response(kind);
+        match &result {
+            Ok(Some(response)) => {
+                let now = self.clock.now();
+                self.metrics.replies_received += 1;
+                let metrics = self.metrics.address_mut(response.address());
+                metrics.replies_received += 1;
+                metrics.last_seen = Some(now);
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::ChecksumMismatch) => {
+                self.metrics.checksum_failures += 1;
+            }
+            _ => (),
+        }
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(Some(response)) => tracing::debug!(
+                address = response.address(),
+                response = ?response,
+                "response received"
+            ),
+            Ok(None) => (),
+            Err(e) => tracing::warn!(error = %e, "invalid response"),
+        }
+
+        result
+    }
+
+    /// Send a message and wait for its response, with the retry policy of
+    /// the port.
+    ///
+    /// The response must come from the device the message was sent to,
+    /// except for broadcast messages. The received bytes buffered by the port
+    /// are discarded before each attempt. The transport itself is not
+    /// drained, since reading it may block: stale bytes still pending in it
+    /// are read by the attempt, and a stale response from another device
+    /// causes a retry.
+    ///
+    /// The message is sent again when no response is received in time, or
+    /// when the response is invalid. After the last attempt, an error of kind
+    /// `ErrorKind::Timeout`, `ErrorKind::ChecksumMismatch` or
+    /// `ErrorKind::UnexpectedResponse` is returned. IO errors are returned
+    /// immediately.
+    ///
+    /// ```rust, ignore
+    /// let mut port = PelcoDPort::new(serialport::open("/dev/ttyS0")?);
+    /// let response = port.transaction(Message::query()?, ResponseKind::Query)?;
+    /// println!("Part number: {:?}", response.part_number());
+    /// ```
+    pub fn transaction(&mut self, message: Message, kind: ResponseKind) -> Result<Response> {
+        let policy = self.retry_policy;
+        self.transaction_with(message, kind, &policy)
     }
+
+    /// Send a message and wait for its response, with the given retry
+    /// policy. See
+    /// [transaction()](struct.PelcoDPort.html#method.transaction).
+    pub fn transaction_with(
+        &mut self,
+        message: Message,
+        kind: ResponseKind,
+        policy: &RetryPolicy,
+    ) -> Result<Response> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "transaction",
+            address = message.address(),
+            command = %message,
+            kind = ?kind
+        )
+        .entered();
+
+        let mut retry = 0;
+        loop {
+            let error = match self.attempt(message, kind, policy.timeout) {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+
+            match error.kind() {
+                ErrorKind::Timeout => {
+                    self.metrics.timeouts += 1;
+                    self.metrics.address_mut(message.address()).timeouts += 1;
+                }
+                ErrorKind::ChecksumMismatch | ErrorKind::UnexpectedResponse => (),
+                _ => return Err(error),
+            }
+
+            if retry >= policy.retries {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %error, attempts = retry + 1, "transaction failed");
+                return Err(error);
+            }
+            retry += 1;
+
+            #[cfg(feature = "tracing")]
+            tracing::info!(error = %error, retry, "retrying transaction");
+
+            self.metrics.retries += 1;
+            self.metrics.address_mut(message.address()).retries += 1;
+            self.clock.sleep(policy.backoff_delay(retry));
+        }
+    }
+
+    fn attempt(
+        &mut self,
+        message: Message,
+        kind: ResponseKind,
+        timeout: Duration,
+    ) -> Result<Response> {
+        self.parser.clear();
+        self.received.clear();
+        self.send_message(message)?;
+
+        let deadline = self.clock.now() + timeout;
+        loop {
+            if let Some(response) = self.receive_response(kind)? {
+                let address = message.address();
+                if !Address::from_byte(address).is_broadcast() && response.address() != address {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedResponse,
+                        &format!("Response from address {}", response.address()),
+                    ));
+                }
+                return Ok(response);
+            }
+
+            let now = self.clock.now();
+            if now >= deadline {
+                return Err(Error::new(ErrorKind::Timeout, "No response"));
+            }
+            self.clock.sleep(POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    fn parse_response(&mut self, kind: ResponseKind) -> Result<Option<Response>> {
+        self.parser.set_kind(kind);
+        if let Some(result) = self.parser.next_response() {
+            return result.map(Some);
+        }
+
+        let mut buf = [0u8; 64];
+        let count = match self.read(&mut buf) {
+            Ok(count) => count,
+            Err(e) if is_no_data(&e) => 0,
+            Err(e) => return Err(e.into()),
+        };
+        self.parser.push(&buf[..count]);
+        self.parser.next_response().transpose()
+    }
+}
+
+pub(crate) fn is_no_data(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+    )
 }
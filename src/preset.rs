@@ -0,0 +1,159 @@
+use crate::error::*;
+
+/// Special functions triggered by calling a reserved preset number.
+///
+/// The preset numbers of these functions depend on the device model, see
+/// [PresetTable](struct.PresetTable.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpecialPreset {
+    /// Rotate 180 degrees.
+    Flip180,
+    /// Go to the zero pan position.
+    GoToZeroPan,
+    /// Set the left limit stop of the scan.
+    LeftLimitStop,
+    /// Set the right limit stop of the scan.
+    RightLimitStop,
+    /// Remote reset (reboot) of the device.
+    RemoteReset,
+    /// Enter the on-screen main menu.
+    Menu,
+    /// Stop the current scan.
+    StopScan,
+    /// Start the random scan.
+    RandomScan,
+    /// Start the frame scan.
+    FrameScan,
+    /// Start the auto scan.
+    AutoScan,
+}
+
+/// Kind of a preset number, as understood by a given device model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// Preset freely usable for storing a position.
+    User(u8),
+    /// Preset reserved for a special function.
+    Special(SpecialPreset),
+}
+
+/// Table of the preset numbers reserved for special functions by a device
+/// model.
+///
+/// Setting a reserved preset usually overwrites the special function, which
+/// can then only be restored by resetting the device. The table allows
+/// catching such mistakes, see
+/// [Message::set_user_preset()](struct.Message.html#method.set_user_preset).
+///
+/// Tables for other models can be defined with
+/// [PresetTable::new()](struct.PresetTable.html#method.new):
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// const MY_DOME: PresetTable = PresetTable::new(
+///     "My dome",
+///     &[(33, SpecialPreset::Flip180), (95, SpecialPreset::Menu)],
+/// );
+/// assert_eq!(Preset::Special(SpecialPreset::Menu), MY_DOME.classify(95));
+/// assert_eq!(Preset::User(34), MY_DOME.classify(34));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresetTable {
+    name: &'static str,
+    reserved: &'static [(u8, SpecialPreset)],
+}
+
+impl PresetTable {
+    /// Special presets common to most devices.
+    pub const GENERIC: PresetTable = PresetTable::new(
+        "Generic",
+        &[
+            (0x21, SpecialPreset::Flip180),
+            (0x22, SpecialPreset::GoToZeroPan),
+        ],
+    );
+
+    /// Special presets of the Spectra III and newer domes.
+    pub const SPECTRA: PresetTable = PresetTable::new(
+        "Spectra",
+        &[
+            (33, SpecialPreset::Flip180),
+            (34, SpecialPreset::GoToZeroPan),
+            (92, SpecialPreset::LeftLimitStop),
+            (93, SpecialPreset::RightLimitStop),
+            (94, SpecialPreset::RemoteReset),
+            (95, SpecialPreset::Menu),
+            (96, SpecialPreset::StopScan),
+            (97, SpecialPreset::RandomScan),
+            (98, SpecialPreset::FrameScan),
+            (99, SpecialPreset::AutoScan),
+        ],
+    );
+
+    /// New table with the given model name and reserved presets.
+    pub const fn new(name: &'static str, reserved: &'static [(u8, SpecialPreset)]) -> PresetTable {
+        PresetTable { name, reserved }
+    }
+
+    /// Name of the device model.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Special function reserved at `preset_id`, if any.
+    pub fn special(&self, preset_id: u8) -> Option<SpecialPreset> {
+        self.reserved
+            .iter()
+            .find(|(id, _)| *id == preset_id)
+            .map(|(_, special)| *special)
+    }
+
+    /// Preset number of the special function, if supported by the model.
+    pub const fn preset_id(&self, special: SpecialPreset) -> Option<u8> {
+        // Written with a loop to be usable in constant expressions
+        let mut i = 0;
+        while i < self.reserved.len() {
+            let (id, s) = self.reserved[i];
+            if s as u8 == special as u8 {
+                return Some(id);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Kind of the preset number for the model.
+    pub fn classify(&self, preset_id: u8) -> Preset {
+        match self.special(preset_id) {
+            Some(special) => Preset::Special(special),
+            None => Preset::User(preset_id),
+        }
+    }
+
+    /// Check that `preset_id` can be used as a user preset.
+    ///
+    /// An error of kind `ErrorKind::ReservedPreset` is returned if the preset
+    /// is reserved for a special function, and `ErrorKind::InvalidValue` if
+    /// it is 0.
+    pub fn check_user_preset(&self, preset_id: u8) -> Result<()> {
+        if preset_id == 0 {
+            return Err(Error::new(ErrorKind::InvalidValue, "Invalid Preset ID"));
+        }
+        match self.special(preset_id) {
+            Some(special) => Err(Error::new(
+                ErrorKind::ReservedPreset(special),
+                &format!(
+                    "Preset {} is reserved for {:?} on {}",
+                    preset_id, special, self.name
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for PresetTable {
+    fn default() -> Self {
+        PresetTable::GENERIC
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+use crate::error::*;
+use crate::message::*;
+use crate::port::PelcoDPort;
+use crate::text::command_name;
+
+/// Priority of a message in a [SendQueue](struct.SendQueue.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// Default priority of a message: `High` for stop commands, `Low` for the
+    /// on-screen display writes, and `Normal` for the others.
+    pub fn of(message: &Message) -> Priority {
+        match command_name(message) {
+            "STOP" => Priority::High,
+            "WRITE_CHAR" | "CLEAR_SCREEN" => Priority::Low,
+            _ => Priority::Normal,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+}
+
+/// Queue of outgoing messages, sent by priority.
+///
+/// Messages of higher priority are sent first, and messages of the same
+/// priority are sent in order. The rate limit of the port applies when
+/// sending.
+///
+/// Since a stop is sent ahead of the other messages, queuing a stop removes
+/// the pending motion commands for the same address, which would otherwise
+/// be sent after it and move the camera again.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let mut queue = SendQueue::new();
+/// queue.push(Message::write_char_to_screen(10, 0, 'A')?);
+/// queue.push(Message::write_char_to_screen(10, 1, 'B')?);
+/// queue.push(MessageBuilder::new(10)?.stop().finalize()?);
+///
+/// assert_eq!(Some(MessageBuilder::new(10)?.stop().finalize()?), queue.pop());
+/// assert_eq!(Some(Message::write_char_to_screen(10, 0, 'A')?), queue.pop());
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SendQueue {
+    queues: [VecDeque<Message>; 3],
+}
+
+impl SendQueue {
+    pub fn new() -> SendQueue {
+        SendQueue::default()
+    }
+
+    /// Add a message with its default priority.
+    pub fn push(&mut self, message: Message) {
+        self.push_with_priority(message, Priority::of(&message));
+    }
+
+    /// Add a message with the given priority.
+    pub fn push_with_priority(&mut self, message: Message, priority: Priority) {
+        if command_name(&message) == "STOP" {
+            let address = message.address();
+            for queue in self.queues.iter_mut() {
+                queue.retain(|m| m.address() != address || Coalescing::of(m) != Coalescing::Latest);
+            }
+        }
+        self.queues[priority.index()].push_back(message);
+    }
+
+    /// Remove the next message to send.
+    pub fn pop(&mut self) -> Option<Message> {
+        self.queues.iter_mut().find_map(|queue| queue.pop_front())
+    }
+
+    /// Number of queued messages.
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(|queue| queue.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the queued messages.
+    pub fn clear(&mut self) {
+        self.queues.iter_mut().for_each(|queue| queue.clear());
+    }
+
+    /// Send the next message, and return it. On error, the message is kept
+    /// in front of the queue.
+    pub fn send_next<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+    ) -> Result<Option<Message>> {
+        let index = match self.queues.iter().position(|queue| !queue.is_empty()) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let message = self.queues[index].pop_front().unwrap();
+        match port.send_message(message) {
+            Ok(()) => Ok(Some(message)),
+            Err(e) => {
+                self.queues[index].push_front(message);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send all the queued messages, and return their number.
+    pub fn flush<T: Read + Write>(&mut self, port: &mut PelcoDPort<T>) -> Result<usize> {
+        let mut count = 0;
+        while self.send_next(port)?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Coalescing rule of a message in a
+/// [CoalescingQueue](struct.CoalescingQueue.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Coalescing {
+    /// Only the latest state matters: the message replaces a pending message
+    /// of the same rule for the same address.
+    Latest,
+    /// The message is always sent, in order.
+    Ordered,
+}
+
+impl Coalescing {
+    /// Rule of a message: `Latest` for the standard motion commands (pan,
+    /// tilt, zoom, focus, iris and stop), and `Ordered` for the others, like
+    /// camera on/off, scan and the extended commands.
+    pub fn of(message: &Message) -> Coalescing {
+        let bytes = message.as_ref();
+        let switches = Command1::SENSE | Command1::AUTO_MANUAL_SCAN | Command1::CAMERA_ON_OFF;
+        if bytes[3] & 1 == 0 && bytes[2] & switches.bits() == 0 {
+            Coalescing::Latest
+        } else {
+            Coalescing::Ordered
+        }
+    }
+}
+
+/// Queue of outgoing messages, collapsing the pending motion updates.
+///
+/// A pushed `Coalescing::Latest` message replaces the last pending message
+/// for the same address if it is also `Coalescing::Latest`, and takes its
+/// place in the queue. Otherwise the message is added at the end. So the
+/// motion updates are only collapsed between two ordered commands, and the
+/// order of the commands of each address is kept.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let left = MessageBuilder::new(10)?.left().pan(Speed::Range(0.5)).finalize()?;
+/// let right = MessageBuilder::new(10)?.right().pan(Speed::Range(0.5)).finalize()?;
+/// let stop = MessageBuilder::new(10)?.stop().finalize()?;
+///
+/// let mut queue = CoalescingQueue::new();
+/// queue.push(left);
+/// queue.push(Message::go_to_preset(10, 5)?);
+/// queue.push(right);
+/// queue.push(stop);
+///
+/// assert_eq!(vec![left, Message::go_to_preset(10, 5)?, stop], queue.messages());
+/// assert_eq!(1, queue.coalesced());
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CoalescingQueue {
+    queue: VecDeque<Message>,
+    coalesced: u64,
+}
+
+impl CoalescingQueue {
+    pub fn new() -> CoalescingQueue {
+        CoalescingQueue::default()
+    }
+
+    /// Add a message, and return `true` if it replaced a pending message.
+    pub fn push(&mut self, message: Message) -> bool {
+        if Coalescing::of(&message) == Coalescing::Latest {
+            let address = message.address();
+            if let Some(last) = self.queue.iter_mut().rev().find(|m| m.address() == address) {
+                if Coalescing::of(last) == Coalescing::Latest {
+                    *last = message;
+                    self.coalesced += 1;
+                    return true;
+                }
+            }
+        }
+        self.queue.push_back(message);
+        false
+    }
+
+    /// Remove the next message to send.
+    pub fn pop(&mut self) -> Option<Message> {
+        self.queue.pop_front()
+    }
+
+    /// Pending messages, in sending order.
+    pub fn messages(&self) -> Vec<Message> {
+        self.queue.iter().copied().collect()
+    }
+
+    /// Number of pending messages.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Remove all the pending messages.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Number of messages replaced since the creation of the queue.
+    pub fn coalesced(&self) -> u64 {
+        self.coalesced
+    }
+
+    /// Send the next message, and return it. On error, the message is kept
+    /// in front of the queue.
+    pub fn send_next<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+    ) -> Result<Option<Message>> {
+        let message = match self.queue.pop_front() {
+            Some(message) => message,
+            None => return Ok(None),
+        };
+        match port.send_message(message) {
+            Ok(()) => Ok(Some(message)),
+            Err(e) => {
+                self.queue.push_front(message);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send all the pending messages, and return their number.
+    pub fn flush<T: Read + Write>(&mut self, port: &mut PelcoDPort<T>) -> Result<usize> {
+        let mut count = 0;
+        while self.send_next(port)?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+}
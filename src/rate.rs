@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Limits on the frames sent by a [PelcoDPort](struct.PelcoDPort.html).
+///
+/// The times are measured between the writes of the frames, so the gap
+/// should include the transmission time of a frame (about 30 ms for 7 bytes
+/// at 2400 baud) when the port buffers the written bytes.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// port.set_rate_limit(RateLimit {
+///     min_gap: Duration::from_millis(40),
+///     max_frames_per_second: 10,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RateLimit {
+    /// Minimum time between two frames. No limit by default.
+    pub min_gap: Duration,
+    /// Maximum number of frames per second sent to each address, or 0 for
+    /// no limit (default).
+    pub max_frames_per_second: u32,
+}
+
+/// Times of the last frames, to apply a `RateLimit`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    last_frame: Option<Duration>,
+    last_frames: BTreeMap<u8, Duration>,
+}
+
+impl RateLimiter {
+    pub(crate) fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    pub(crate) fn set_limit(&mut self, limit: RateLimit) {
+        self.limit = limit;
+    }
+
+    /// Wait needed at `now` before sending a frame to `address`.
+    pub(crate) fn delay(&self, address: u8, now: Duration) -> Duration {
+        let mut ready = now;
+        if let Some(last) = self.last_frame {
+            ready = ready.max(last + self.limit.min_gap);
+        }
+        if self.limit.max_frames_per_second > 0 {
+            if let Some(last) = self.last_frames.get(&address) {
+                ready =
+                    ready.max(*last + Duration::from_secs(1) / self.limit.max_frames_per_second);
+            }
+        }
+        ready - now
+    }
+
+    /// Record a frame sent to `address` at `now`.
+    pub(crate) fn record(&mut self, address: u8, now: Duration) {
+        self.last_frame = Some(now);
+        self.last_frames.insert(address, now);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::*;
+use crate::message::checksum;
+
+const SYNC_BYTE: u8 = 0xFF;
+const PART_NUMBER_SIZE: usize = 15;
+
+/// Kinds of response sent by the devices.
+///
+/// The kind of a response cannot be reliably deduced from its bytes, so it
+/// must be known from the command which was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseKind {
+    /// General response, 4 bytes including the alarm byte.
+    General,
+    /// Extended response, 7 bytes.
+    Extended,
+    /// Query response, 18 bytes including the part number.
+    Query,
+}
+
+impl ResponseKind {
+    /// Size of the response in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ResponseKind::General => 4,
+            ResponseKind::Extended => 7,
+            ResponseKind::Query => 3 + PART_NUMBER_SIZE,
+        }
+    }
+}
+
+/// Response received from a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    /// General response, with the state of the alarm inputs (bit 0 for alarm
+    /// 1 to bit 7 for alarm 8).
+    General { address: u8, alarms: u8 },
+    /// Extended response, with the words between address and checksum.
+    Extended { address: u8, words: [u8; 4] },
+    /// Query response, with the part number of the device.
+    Query {
+        address: u8,
+        part_number: [u8; PART_NUMBER_SIZE],
+    },
+}
+
+impl Response {
+    /// Parse a complete response of the given kind. An error is returned if
+    /// the size or sync byte is wrong, or `ErrorKind::ChecksumMismatch` if
+    /// the checksum is wrong.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// let response = Response::parse(ResponseKind::General, &[0xFF, 0x0A, 0x05, 0x0F]).unwrap();
+    /// assert_eq!(Response::General { address: 10, alarms: 0x05 }, response);
+    /// ```
+    pub fn parse(kind: ResponseKind, bytes: &[u8]) -> Result<Response> {
+        if bytes.len() != kind.size() {
+            return Err(Error::new(ErrorKind::InvalidValue, "Invalid response size"));
+        }
+        if bytes[0] != SYNC_BYTE {
+            return Err(Error::new(ErrorKind::InvalidValue, "Invalid sync byte"));
+        }
+        let last = bytes.len() - 1;
+        if checksum(&bytes[1..last]) != bytes[last] {
+            return Err(Error::new(ErrorKind::ChecksumMismatch, "Invalid checksum"));
+        }
+
+        let address = bytes[1];
+        Ok(match kind {
+            ResponseKind::General => Response::General {
+                address,
+                alarms: bytes[2],
+            },
+            ResponseKind::Extended => {
+                let mut words = [0u8; 4];
+                words.copy_from_slice(&bytes[2..last]);
+                Response::Extended { address, words }
+            }
+            ResponseKind::Query => {
+                let mut part_number = [0u8; PART_NUMBER_SIZE];
+                part_number.copy_from_slice(&bytes[2..last]);
+                Response::Query {
+                    address,
+                    part_number,
+                }
+            }
+        })
+    }
+
+    /// Address of the responding device.
+    pub fn address(&self) -> u8 {
+        match *self {
+            Response::General { address, .. } => address,
+            Response::Extended { address, .. } => address,
+            Response::Query { address, .. } => address,
+        }
+    }
+
+    /// Kind of the response.
+    pub fn kind(&self) -> ResponseKind {
+        match self {
+            Response::General { .. } => ResponseKind::General,
+            Response::Extended { .. } => ResponseKind::Extended,
+            Response::Query { .. } => ResponseKind::Query,
+        }
+    }
+
+    /// Part number of a query response, without the padding spaces.
+    pub fn part_number(&self) -> Option<String> {
+        match self {
+            Response::Query { part_number, .. } => Some(
+                String::from_utf8_lossy(part_number)
+                    .trim_end_matches([' ', '\0'])
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Streaming parser extracting responses from received bytes.
+///
+/// The bytes preceding a sync byte are skipped. When a frame has a wrong
+/// checksum, only its sync byte is dropped so that the parser can
+/// resynchronize on the next one.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// let mut parser = ResponseParser::new(ResponseKind::General);
+/// parser.push(&[0x00, 0xFF, 0x0A]);
+/// assert!(parser.next_response().is_none());
+/// parser.push(&[0x01, 0x0B]);
+/// let response = parser.next_response().unwrap().unwrap();
+/// assert_eq!(Response::General { address: 10, alarms: 1 }, response);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseParser {
+    kind: ResponseKind,
+    buffer: Vec<u8>,
+}
+
+impl ResponseParser {
+    /// New parser for responses of the given kind.
+    pub fn new(kind: ResponseKind) -> ResponseParser {
+        ResponseParser {
+            kind,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Kind of the expected responses.
+    pub fn kind(&self) -> ResponseKind {
+        self.kind
+    }
+
+    /// Change the kind of the expected responses.
+    pub fn set_kind(&mut self, kind: ResponseKind) {
+        self.kind = kind;
+    }
+
+    /// Add received bytes.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Number of bytes waiting to be parsed.
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discard the pending bytes.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Next complete response, or `None` if more bytes are needed.
+    pub fn next_response(&mut self) -> Option<Result<Response>> {
+        match self.buffer.iter().position(|&b| b == SYNC_BYTE) {
+            Some(start) => {
+                self.buffer.drain(..start);
+            }
+            None => {
+                self.buffer.clear();
+                return None;
+            }
+        }
+
+        let size = self.kind.size();
+        if self.buffer.len() < size {
+            return None;
+        }
+
+        let result = Response::parse(self.kind, &self.buffer[..size]);
+        match result {
+            Ok(_) => self.buffer.drain(..size),
+            Err(_) => self.buffer.drain(..1),
+        };
+        Some(result)
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+/// Timeout and retries of the request/response transactions of a
+/// [PelcoDPort](struct.PelcoDPort.html).
+///
+/// The wait before a retry is `backoff` for the first retry, and doubles for
+/// each following retry.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// let policy = RetryPolicy {
+///     retries: 3,
+///     ..RetryPolicy::default()
+/// };
+/// assert_eq!(Duration::from_millis(400), policy.backoff_delay(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Time to wait for the reply of each attempt.
+    pub timeout: Duration,
+    /// Number of attempts after the first one.
+    pub retries: u32,
+    /// Wait before the first retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Single attempt, waiting `timeout` for the reply.
+    pub fn no_retry(timeout: Duration) -> RetryPolicy {
+        RetryPolicy {
+            timeout,
+            retries: 0,
+            backoff: Duration::from_secs(0),
+        }
+    }
+
+    /// Wait before the given retry, starting from 1.
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.backoff.saturating_mul(factor)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// 500 ms timeout, and 2 retries with a backoff of 100 ms.
+    fn default() -> Self {
+        RetryPolicy {
+            timeout: Duration::from_millis(500),
+            retries: 2,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
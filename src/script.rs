@@ -0,0 +1,348 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::*;
+use crate::message::*;
+use crate::port::PelcoDPort;
+
+/// Single step of a [Script](struct.Script.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptStep {
+    /// Send a message.
+    Send(Message),
+    /// Wait before the next step.
+    Wait(Duration),
+}
+
+impl fmt::Display for ScriptStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptStep::Send(message) => write!(f, "send {:X}  ; {}", message, message),
+            ScriptStep::Wait(duration) => write!(f, "wait {}ms", duration.as_millis()),
+        }
+    }
+}
+
+/// Sequence of camera commands, parsed from a line-based text script.
+///
+/// Each line holds one command, and `#` starts a comment. The commands are:
+///
+/// | Command                              | Action                                   |
+/// |--------------------------------------|------------------------------------------|
+/// | `camera 10`                          | Select the device address                |
+/// | `goto 5`                             | Call a preset                            |
+/// | `preset set 5`, `preset clear 5`     | Set or clear a preset                    |
+/// | `pan left 0.4`, `pan right turbo`    | Pan, with speed from 0 to 1 or `turbo`   |
+/// | `tilt up 0.4`, `tilt down 0.4`       | Tilt, with speed from 0 to 1             |
+/// | `zoom in`, `zoom out`                | Zoom                                     |
+/// | `focus near`, `focus far`            | Focus                                    |
+/// | `stop`                               | Stop all movements                       |
+/// | `aux on 1`, `aux off 1`              | Set or clear an auxiliary                |
+/// | `pattern run 1`                      | Run a pattern                            |
+/// | `flip`                               | Rotate 180 degrees                       |
+/// | `send addr=10 GOTO_PRESET 5`         | Send a message in text format            |
+/// | `wait 3s`                            | Wait, in `ms`, `s` or `m`                |
+/// | `repeat 3 {` ... `}`                 | Repeat the enclosed commands             |
+///
+/// Movement commands accept a duration with `for`, like
+/// `pan left 0.4 for 2s`, after which the camera is stopped.
+///
+/// The repeated commands are expanded when parsing, so the repeat count is
+/// limited to [Script::MAX_REPEAT](struct.Script.html#associatedconstant.MAX_REPEAT)
+/// and the expanded script to
+/// [Script::MAX_STEPS](struct.Script.html#associatedconstant.MAX_STEPS) steps.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// # fn example() -> Result<()> {
+/// let script: Script = "
+///     camera 10
+///     repeat 2 {
+///         goto 1
+///         wait 3s
+///     }
+///     pan left 0.5 for 500ms
+/// ".parse()?;
+///
+/// assert_eq!(
+///     &[
+///         ScriptStep::Send(Message::go_to_preset(10, 1)?),
+///         ScriptStep::Wait(Duration::from_secs(3)),
+///         ScriptStep::Send(Message::go_to_preset(10, 1)?),
+///         ScriptStep::Wait(Duration::from_secs(3)),
+///         ScriptStep::Send(MessageBuilder::new(10)?.left().pan(Speed::Range(0.5)).finalize()?),
+///         ScriptStep::Wait(Duration::from_millis(500)),
+///         ScriptStep::Send(MessageBuilder::new(10)?.stop().finalize()?),
+///     ],
+///     script.steps()
+/// );
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Script {
+    steps: Vec<ScriptStep>,
+}
+
+impl Script {
+    /// Maximum count of a `repeat` command.
+    pub const MAX_REPEAT: usize = 1000;
+
+    /// Maximum number of steps of a parsed script, after the expansion of the
+    /// `repeat` commands.
+    pub const MAX_STEPS: usize = 100_000;
+
+    pub fn new(steps: Vec<ScriptStep>) -> Script {
+        Script { steps }
+    }
+
+    /// Steps of the script.
+    pub fn steps(&self) -> &[ScriptStep] {
+        &self.steps
+    }
+
+    /// Total waiting time of the script.
+    pub fn duration(&self) -> Duration {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                ScriptStep::Wait(duration) => *duration,
+                ScriptStep::Send(_) => Duration::from_secs(0),
+            })
+            .sum()
+    }
+
+    /// Listing of the frames and waits of the script, one step per line.
+    pub fn dry_run(&self) -> String {
+        self.steps
+            .iter()
+            .map(|step| format!("{}\n", step))
+            .collect()
+    }
+
+    /// Run the script on the port, waiting with the clock of the port.
+    pub fn run<T: Read + Write>(&self, port: &mut PelcoDPort<T>) -> Result<()> {
+        for step in self.steps.iter() {
+            match step {
+                ScriptStep::Send(message) => port.send_message(*message)?,
+                ScriptStep::Wait(duration) => port.clock().sleep(*duration),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Script {
+    type Err = Error;
+
+    /// Parse a script. The returned error is of kind `ErrorKind::Script`
+    /// with the line number of the faulty command.
+    fn from_str(s: &str) -> Result<Script> {
+        let mut parser = Parser {
+            lines: s.lines().enumerate().collect(),
+            next: 0,
+            address: None,
+        };
+        let steps = parser.parse_block(None)?;
+        Ok(Script { steps })
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<(usize, &'a str)>,
+    next: usize,
+    address: Option<u8>,
+}
+
+impl<'a> Parser<'a> {
+    // Parse until the closing brace of the block opened at `opening` line
+    fn parse_block(&mut self, opening: Option<usize>) -> Result<Vec<ScriptStep>> {
+        let mut steps = Vec::new();
+
+        while self.next < self.lines.len() {
+            let (index, line) = self.lines[self.next];
+            let line_no = index + 1;
+            self.next += 1;
+
+            let line = line.split('#').next().unwrap_or("").trim();
+            let tokens: Vec<String> = line.split_whitespace().map(|t| t.to_lowercase()).collect();
+            let tokens: Vec<&str> = tokens.iter().map(|t| t.as_str()).collect();
+
+            match tokens.as_slice() {
+                [] => (),
+                ["}"] if opening.is_some() => return Ok(steps),
+                ["repeat", count, "{"] => {
+                    let count: usize = count
+                        .parse()
+                        .map_err(|_| script_error(line_no, "invalid repeat count"))?;
+                    if count > Script::MAX_REPEAT {
+                        return Err(script_error(line_no, "repeat count too large"));
+                    }
+                    let block = self.parse_block(Some(line_no))?;
+                    let total = block.len() * count + steps.len();
+                    if total > Script::MAX_STEPS {
+                        return Err(script_error(line_no, "too many steps"));
+                    }
+                    for _ in 0..count {
+                        steps.extend_from_slice(&block);
+                    }
+                }
+                ["send", ..] => {
+                    let text = line["send".len()..].trim();
+                    let message = text
+                        .parse()
+                        .map_err(|e: Error| script_error(line_no, &e.to_string()))?;
+                    steps.push(ScriptStep::Send(message));
+                }
+                _ => self
+                    .parse_command(&tokens, &mut steps)
+                    .map_err(|e| script_error(line_no, &e.to_string()))?,
+            }
+        }
+
+        match opening {
+            Some(line_no) => Err(script_error(line_no, "missing closing brace")),
+            None => Ok(steps),
+        }
+    }
+
+    fn parse_command(&mut self, tokens: &[&str], steps: &mut Vec<ScriptStep>) -> Result<()> {
+        // Movement duration suffix
+        let (tokens, duration) = match tokens {
+            [command @ .., "for", duration] => (command, Some(parse_duration(duration)?)),
+            _ => (tokens, None),
+        };
+
+        let for_error = || syntax_error("'for' is only valid for movements");
+        match tokens {
+            ["camera", _] | ["wait", _] if duration.is_some() => return Err(for_error()),
+            ["camera", address] => {
+                self.address = Some(parse_value(address)?);
+                return Ok(());
+            }
+            ["wait", duration] => {
+                steps.push(ScriptStep::Wait(parse_duration(duration)?));
+                return Ok(());
+            }
+            _ => (),
+        }
+
+        let address = self
+            .address
+            .ok_or_else(|| syntax_error("no camera selected"))?;
+        let mut builder = MessageBuilder::new(address)?;
+        let mut movement = true;
+
+        let message = match tokens {
+            ["goto", preset_id] | ["preset", "goto", preset_id] => {
+                movement = false;
+                Message::go_to_preset(address, parse_value(preset_id)?)?
+            }
+            ["preset", "set", preset_id] => {
+                movement = false;
+                Message::set_preset(address, parse_value(preset_id)?)?
+            }
+            ["preset", "clear", preset_id] => {
+                movement = false;
+                Message::clear_preset(address, parse_value(preset_id)?)?
+            }
+            ["aux", "on", aux_id] => {
+                movement = false;
+                Message::set_auxiliary(address, 0, parse_value(aux_id)?)?
+            }
+            ["aux", "off", aux_id] => {
+                movement = false;
+                Message::clear_auxiliary(address, 0, parse_value(aux_id)?)?
+            }
+            ["pattern", "run", pattern_id] => {
+                movement = false;
+                Message::run_pattern(address, parse_value(pattern_id)?)?
+            }
+            ["flip"] => {
+                movement = false;
+                Message::flip_180(address)?
+            }
+            ["stop"] => {
+                movement = false;
+                builder.stop().finalize()?
+            }
+            ["pan", "left", speed] => builder.left().pan(parse_speed(speed)?).finalize()?,
+            ["pan", "right", speed] => builder.right().pan(parse_speed(speed)?).finalize()?,
+            ["tilt", "up", speed] => builder.up().tilt(parse_speed(speed)?).finalize()?,
+            ["tilt", "down", speed] => builder.down().tilt(parse_speed(speed)?).finalize()?,
+            ["zoom", "in"] => builder.zoom_in().finalize()?,
+            ["zoom", "out"] => builder.zoom_out().finalize()?,
+            ["focus", "near"] => builder.focus_near().finalize()?,
+            ["focus", "far"] => builder.focus_far().finalize()?,
+            _ => {
+                return Err(syntax_error(&format!(
+                    "unknown command '{}'",
+                    tokens.join(" ")
+                )))
+            }
+        };
+
+        steps.push(ScriptStep::Send(message));
+        match (duration, movement) {
+            (Some(duration), true) => {
+                steps.push(ScriptStep::Wait(duration));
+                steps.push(ScriptStep::Send(
+                    MessageBuilder::new(address)?.stop().finalize()?,
+                ));
+            }
+            (Some(_), false) => return Err(for_error()),
+            (None, _) => (),
+        }
+        Ok(())
+    }
+}
+
+fn syntax_error(description: &str) -> Error {
+    Error::new(ErrorKind::InvalidValue, description)
+}
+
+fn script_error(line: usize, description: &str) -> Error {
+    Error::new(
+        ErrorKind::Script { line },
+        &format!("line {}: {}", line, description),
+    )
+}
+
+fn parse_value<N: FromStr>(token: &str) -> Result<N> {
+    token
+        .parse()
+        .map_err(|_| syntax_error(&format!("invalid number '{}'", token)))
+}
+
+fn parse_speed(token: &str) -> Result<Speed> {
+    if token == "turbo" {
+        return Ok(Speed::Turbo);
+    }
+    match token.parse::<f32>() {
+        Ok(speed) if (0.0..=1.0).contains(&speed) => Ok(Speed::Range(speed)),
+        _ => Err(syntax_error(&format!("invalid speed '{}'", token))),
+    }
+}
+
+fn parse_duration(token: &str) -> Result<Duration> {
+    let error = || syntax_error(&format!("invalid duration '{}'", token));
+    // Unit in nanoseconds
+    let (value, unit) = if let Some(value) = token.strip_suffix("ms") {
+        (value, 1e6)
+    } else if let Some(value) = token.strip_suffix('s') {
+        (value, 1e9)
+    } else if let Some(value) = token.strip_suffix('m') {
+        (value, 60e9)
+    } else {
+        return Err(error());
+    };
+    match value.parse::<f64>() {
+        Ok(value) if value >= 0.0 && value * unit < u64::MAX as f64 => {
+            Ok(Duration::from_nanos((value * unit).round() as u64))
+        }
+        _ => Err(error()),
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+
+use crate::duplex::RequestToSend;
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+use crate::response::ResponseKind;
+use crate::retry::RetryPolicy;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+const DETECT_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Serial port opened by [PelcoDPort::open()](struct.PelcoDPort.html#method.open).
+pub type SerialTransport = serialport::TTYPort;
+
+/// Baud rates of the Pelco D devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Baud {
+    B2400,
+    B4800,
+    B9600,
+}
+
+impl Baud {
+    /// All the baud rates, from the most common one.
+    pub const ALL: [Baud; 3] = [Baud::B2400, Baud::B4800, Baud::B9600];
+
+    /// Rate in bits per second.
+    pub fn rate(self) -> u32 {
+        match self {
+            Baud::B2400 => 2400,
+            Baud::B4800 => 4800,
+            Baud::B9600 => 9600,
+        }
+    }
+}
+
+impl PelcoDPort<SerialTransport> {
+    /// Open a serial port, configured with 8 data bits, no parity, 1 stop bit
+    /// and no flow control. The port is locked for exclusive access, and the
+    /// reads time out after 100 ms.
+    ///
+    /// ```rust, no_run
+    /// # use pelcodrs::*;
+    /// # fn example() -> Result<()> {
+    /// let mut port = PelcoDPort::open("/dev/ttyUSB0", Baud::B2400)?;
+    /// port.send_message(Message::go_to_preset(10, 5)?)?;
+    /// # Ok(())}
+    /// ```
+    pub fn open(path: &str, baud: Baud) -> Result<PelcoDPort<SerialTransport>> {
+        let mut serial = serialport::new(path, baud.rate())
+            .data_bits(DataBits::Eight)
+            .parity(Parity::None)
+            .stop_bits(StopBits::One)
+            .flow_control(FlowControl::None)
+            .timeout(READ_TIMEOUT)
+            .open_native()?;
+        serial.set_exclusive(true)?;
+        Ok(PelcoDPort::new(serial))
+    }
+
+    /// Open a serial port like
+    /// [open()](struct.PelcoDPort.html#method.open), at the baud rate of
+    /// the device at `address`.
+    ///
+    /// The rates of `Baud::ALL` are tried in order, until the device answers
+    /// a query. An error of kind `ErrorKind::Timeout` is returned if the
+    /// device answers at none of them.
+    pub fn open_detect(path: &str, address: u8) -> Result<(PelcoDPort<SerialTransport>, Baud)> {
+        let mut port = PelcoDPort::open(path, Baud::ALL[0])?;
+        let query = Message::query_to(address);
+        let policy = RetryPolicy {
+            retries: 1,
+            ..RetryPolicy::no_retry(DETECT_TIMEOUT)
+        };
+
+        for baud in Baud::ALL.iter() {
+            port.set_baud(*baud)?;
+            port.get_ref().clear(ClearBuffer::Input)?;
+            match port.transaction_with(query, ResponseKind::Query, &policy) {
+                Ok(_) => return Ok((port, *baud)),
+                Err(e) => match e.kind() {
+                    ErrorKind::Timeout
+                    | ErrorKind::ChecksumMismatch
+                    | ErrorKind::UnexpectedResponse => continue,
+                    _ => return Err(e),
+                },
+            }
+        }
+
+        Err(Error::new(
+            ErrorKind::Timeout,
+            &format!("No answer from address {} at any baud rate", address),
+        ))
+    }
+
+    /// Change the baud rate.
+    pub fn set_baud(&mut self, baud: Baud) -> Result<()> {
+        Ok(self.get_mut().set_baud_rate(baud.rate())?)
+    }
+
+    /// Change the read timeout.
+    pub fn set_read_timeout(&mut self, timeout: Duration) -> Result<()> {
+        Ok(self.get_mut().set_timeout(timeout)?)
+    }
+}
+
+impl RequestToSend for SerialTransport {
+    fn set_request_to_send(&mut self, level: bool) -> std::io::Result<()> {
+        Ok(self.write_request_to_send(level)?)
+    }
+}
+
+impl From<serialport::Error> for Error {
+    fn from(e: serialport::Error) -> Self {
+        let description = e.to_string();
+        Error::new(ErrorKind::Io(e.into()), &description)
+    }
+}
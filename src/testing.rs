@@ -0,0 +1,435 @@
+//! Helpers for testing the code using a [PelcoDPort](../struct.PelcoDPort.html),
+//! enabled by the `testing` feature.
+//!
+//! [MockTransport](struct.MockTransport.html) stands for the device: it
+//! records the frames sent by the port, replies to the expected messages, and
+//! can inject failures. The [assert_sent!](../macro.assert_sent.html) and
+//! [assert_not_sent!](../macro.assert_not_sent.html) macros check the sent
+//! frames.
+//!
+//! ```rust
+//! use pelcodrs::testing::MockTransport;
+//! use pelcodrs::*;
+//!
+//! # fn example() -> Result<()> {
+//! let mock = MockTransport::new();
+//! mock.expect(Message::go_to_preset(1, 5)?)
+//!     .reply(&[0xFF, 0x01, 0x00, 0x01]);
+//!
+//! let mut port = PelcoDPort::new(mock.clone());
+//! let response = port.transaction(Message::go_to_preset(1, 5)?, ResponseKind::General)?;
+//! assert_eq!(Response::General { address: 1, alarms: 0 }, response);
+//!
+//! assert_sent!(mock, Message::go_to_preset(1, 5)?);
+//! mock.verify();
+//! # Ok(())}
+//! # example().unwrap();
+//! ```
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::clock::*;
+use crate::message::{checksum, Message};
+
+const FRAME_SIZE: usize = 7;
+const SYNC_BYTE: u8 = 0xFF;
+
+/// Failure injected in a [MockTransport](struct.MockTransport.html), for the
+/// next read or write only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// The next write fails with an error of this kind. The frame being
+    /// written is dropped.
+    WriteError(std::io::ErrorKind),
+    /// The next write accepts only this number of bytes.
+    ShortWrite(usize),
+    /// The next read fails with an error of this kind.
+    ReadError(std::io::ErrorKind),
+    /// The next reply becomes readable only after this delay, measured from
+    /// the next read by the clock of the mock.
+    ReadDelay(Duration),
+    /// The checksum of the next reply is corrupted, by inverting its last
+    /// byte.
+    CorruptedRead,
+}
+
+#[derive(Debug)]
+struct Expected {
+    id: u64,
+    message: Message,
+    reply: Vec<u8>,
+    delay: Duration,
+    corrupted: bool,
+}
+
+#[derive(Debug)]
+struct MockState {
+    clock: Arc<dyn Clock>,
+    next_id: u64,
+    expected: VecDeque<Expected>,
+    unexpected: Vec<Message>,
+    // Complete frames, with the time at which they were written
+    sent: Vec<(Duration, Message)>,
+    written: Vec<u8>,
+    writes: usize,
+    // Written bytes not forming a complete frame yet
+    partial: Vec<u8>,
+    // Replies, with the time from which they can be read
+    replies: VecDeque<(Duration, Vec<u8>)>,
+    write_failures: VecDeque<Failure>,
+    read_failures: VecDeque<Failure>,
+}
+
+impl MockState {
+    fn receive(&mut self, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        loop {
+            // Resynchronize on the next sync byte
+            match self.partial.iter().position(|&byte| byte == SYNC_BYTE) {
+                Some(start) => drop(self.partial.drain(..start)),
+                None => self.partial.clear(),
+            }
+            if self.partial.len() < FRAME_SIZE {
+                return;
+            }
+            let frame = &self.partial[..FRAME_SIZE];
+            if checksum(&frame[1..FRAME_SIZE - 1]) == frame[FRAME_SIZE - 1] {
+                let message = Message::try_from(frame).unwrap();
+                self.partial.drain(..FRAME_SIZE);
+                self.receive_frame(message);
+            } else {
+                self.partial.remove(0);
+            }
+        }
+    }
+
+    fn receive_frame(&mut self, message: Message) {
+        let now = self.clock.now();
+        self.sent.push((now, message));
+        let matches = match self.expected.front() {
+            Some(expected) => expected.message == message,
+            None => false,
+        };
+        if !matches {
+            self.unexpected.push(message);
+            return;
+        }
+
+        let expected = self.expected.pop_front().unwrap();
+        let mut reply = expected.reply;
+        if expected.corrupted {
+            if let Some(last) = reply.last_mut() {
+                *last ^= 0xFF;
+            }
+        }
+        if !reply.is_empty() {
+            let ready = now + expected.delay;
+            self.replies.push_back((ready, reply));
+        }
+    }
+}
+
+/// Scripted transport simulating a device.
+///
+/// The messages written to the transport are compared in order with the
+/// expected ones, and each expected message gets its reply. Any other message,
+/// including the messages sent once the expected ones are used up, is
+/// reported as unexpected by
+/// [verify()](struct.MockTransport.html#method.verify).
+///
+/// Clones share the same state, so that a test can keep a handle on a mock
+/// given to a port.
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> MockTransport {
+        MockTransport {
+            state: Arc::new(Mutex::new(MockState {
+                clock: Arc::new(SystemClock::new()),
+                next_id: 0,
+                expected: VecDeque::new(),
+                unexpected: Vec::new(),
+                sent: Vec::new(),
+                written: Vec::new(),
+                writes: 0,
+                partial: Vec::new(),
+                replies: VecDeque::new(),
+                write_failures: VecDeque::new(),
+                read_failures: VecDeque::new(),
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Change the clock used for the reply delays, which should be the clock
+    /// of the port.
+    pub fn set_clock<C: Clock + 'static>(&self, clock: C) -> &Self {
+        self.state().clock = Arc::new(clock);
+        self
+    }
+
+    /// Expect a message, after the previously expected ones. The reply and
+    /// its delay are set on the returned
+    /// [Expectation](struct.Expectation.html).
+    pub fn expect(&self, message: Message) -> Expectation {
+        let mut state = self.state();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.expected.push_back(Expected {
+            id,
+            message,
+            reply: Vec::new(),
+            delay: Duration::from_secs(0),
+            corrupted: false,
+        });
+        Expectation {
+            state: self.state.clone(),
+            id,
+        }
+    }
+
+    /// Send bytes which are not a reply to a message, like the alarm reports
+    /// of a device. They are readable after the pending replies.
+    pub fn unsolicited(&self, bytes: &[u8]) -> &Self {
+        let mut state = self.state();
+        let now = state.clock.now();
+        state.replies.push_back((now, bytes.to_vec()));
+        self
+    }
+
+    /// Inject a failure in the next read or write.
+    pub fn inject(&self, failure: Failure) -> &Self {
+        let mut state = self.state();
+        match failure {
+            Failure::WriteError(_) | Failure::ShortWrite(_) => {
+                state.write_failures.push_back(failure)
+            }
+            Failure::ReadError(_) | Failure::ReadDelay(_) | Failure::CorruptedRead => {
+                state.read_failures.push_back(failure)
+            }
+        }
+        self
+    }
+
+    /// Complete frames written to the transport, in order.
+    pub fn sent(&self) -> Vec<Message> {
+        self.state()
+            .sent
+            .iter()
+            .map(|(_, message)| *message)
+            .collect()
+    }
+
+    /// Complete frames written to the transport, in order, with the time at
+    /// which they were written, measured by the clock of the mock.
+    pub fn sent_at(&self) -> Vec<(Duration, Message)> {
+        self.state().sent.clone()
+    }
+
+    /// All the bytes written to the transport.
+    pub fn written(&self) -> Vec<u8> {
+        self.state().written.clone()
+    }
+
+    /// Number of calls to `write()`, for checking how the frames are grouped.
+    pub fn writes(&self) -> usize {
+        self.state().writes
+    }
+
+    /// Forget the frames written so far.
+    pub fn clear_sent(&self) {
+        let mut state = self.state();
+        state.sent.clear();
+        state.written.clear();
+        state.writes = 0;
+    }
+
+    /// Number of expected messages not sent yet.
+    pub fn pending(&self) -> usize {
+        self.state().expected.len()
+    }
+
+    /// Check that all the expected messages were sent, and no other message
+    /// was sent in their place.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the list of the missing or unexpected messages.
+    pub fn verify(&self) {
+        let state = self.state();
+        let mut problems = Vec::new();
+        for message in state.unexpected.iter() {
+            problems.push(format!("unexpected message {}", message));
+        }
+        for expected in state.expected.iter() {
+            problems.push(format!("expected message {} not sent", expected.message));
+        }
+        if !problems.is_empty() {
+            panic!("{}", problems.join("\n"));
+        }
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        MockTransport::new()
+    }
+}
+
+impl Read for MockTransport {
+    fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        let mut state = self.state();
+        let now = state.clock.now();
+        match state.read_failures.front().copied() {
+            Some(Failure::ReadError(kind)) => {
+                state.read_failures.pop_front();
+                return Err(std::io::Error::new(kind, "injected read error"));
+            }
+            // Kept until there is a reply to delay or corrupt
+            Some(failure) if !state.replies.is_empty() => {
+                state.read_failures.pop_front();
+                let (ready, reply) = state.replies.front_mut().unwrap();
+                match failure {
+                    Failure::ReadDelay(delay) => *ready = (*ready).max(now) + delay,
+                    _ => {
+                        if let Some(last) = reply.last_mut() {
+                            *last ^= 0xFF;
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        let mut count = 0;
+        while count < buf.len() {
+            let reply = match state.replies.front_mut() {
+                Some((ready, reply)) if *ready <= now => reply,
+                _ => break,
+            };
+            let size = reply.len().min(buf.len() - count);
+            buf[count..count + size].copy_from_slice(&reply[..size]);
+            reply.drain(..size);
+            count += size;
+            if reply.is_empty() {
+                state.replies.pop_front();
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl Write for MockTransport {
+    fn write(&mut self, buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
+        let mut state = self.state();
+        state.writes += 1;
+        let count = match state.write_failures.pop_front() {
+            Some(Failure::WriteError(kind)) => {
+                state.partial.clear();
+                return Err(std::io::Error::new(kind, "injected write error"));
+            }
+            // The rest of the frame is expected in the next write
+            Some(Failure::ShortWrite(max)) => buf.len().min(max),
+            _ => buf.len(),
+        };
+
+        state.written.extend_from_slice(&buf[..count]);
+        state.receive(&buf[..count]);
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+/// Expected message of a [MockTransport](struct.MockTransport.html), whose
+/// reply can be set until the message is sent.
+#[derive(Debug)]
+pub struct Expectation {
+    state: Arc<Mutex<MockState>>,
+    id: u64,
+}
+
+impl Expectation {
+    fn update<F: FnOnce(&mut Expected)>(&self, f: F) -> &Self {
+        let mut state = self.state.lock().unwrap();
+        if let Some(expected) = state.expected.iter_mut().find(|e| e.id == self.id) {
+            f(expected);
+        }
+        self
+    }
+
+    /// Bytes sent back after the message.
+    pub fn reply(&self, bytes: &[u8]) -> &Self {
+        self.update(|expected| expected.reply.extend_from_slice(bytes))
+    }
+
+    /// Time between the message and its reply, measured by the clock of the
+    /// mock.
+    pub fn delay(&self, delay: Duration) -> &Self {
+        self.update(|expected| expected.delay = delay)
+    }
+
+    /// Corrupt the checksum of the reply, by inverting its last byte.
+    pub fn corrupted(&self) -> &Self {
+        self.update(|expected| expected.corrupted = true)
+    }
+}
+
+/// Assert that the given messages were sent through a
+/// [MockTransport](testing/struct.MockTransport.html).
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use pelcodrs::testing::MockTransport;
+/// # fn example() -> Result<()> {
+/// let mock = MockTransport::new();
+/// let mut port = PelcoDPort::new(mock.clone());
+/// port.send_message(Message::go_to_preset(1, 5)?)?;
+/// port.send_message(Message::clear_screen(1)?)?;
+/// assert_sent!(mock, Message::go_to_preset(1, 5)?, Message::clear_screen(1)?);
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[macro_export]
+macro_rules! assert_sent {
+    ($mock:expr, $($message:expr),+ $(,)?) => {{
+        let sent: Vec<$crate::Message> = $mock.sent();
+        $(
+            let message: $crate::Message = $message;
+            if !sent.contains(&message) {
+                panic!(
+                    "{} was not sent, the sent messages are: [{}]",
+                    message,
+                    sent.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(", ")
+                );
+            }
+        )+
+    }};
+}
+
+/// Assert that the given messages were not sent through a
+/// [MockTransport](testing/struct.MockTransport.html).
+#[macro_export]
+macro_rules! assert_not_sent {
+    ($mock:expr, $($message:expr),+ $(,)?) => {{
+        let sent: Vec<$crate::Message> = $mock.sent();
+        $(
+            let message: $crate::Message = $message;
+            if sent.contains(&message) {
+                panic!("{} was sent", message);
+            }
+        )+
+    }};
+}
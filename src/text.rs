@@ -0,0 +1,446 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::*;
+use crate::message::*;
+
+const SYNC_BYTE: u8 = 0xFF;
+
+// Arguments layout of the extended commands, as the words after the address
+#[derive(Clone, Copy)]
+enum Args {
+    // No argument, all words are 0
+    None,
+    // Argument in the last word
+    Value,
+    // 16-bit argument in the two last words
+    Word,
+    // Column and character
+    Char,
+    // New value or delta, in the two last words
+    Adjust,
+}
+
+const EXTENDED: &[(u8, &str, Args)] = &[
+    (0x03, "SET_PRESET", Args::Value),
+    (0x05, "CLEAR_PRESET", Args::Value),
+    (0x07, "GOTO_PRESET", Args::Value),
+    (0x09, "SET_AUX", Args::Value),
+    (0x0B, "CLEAR_AUX", Args::Value),
+    (0x0F, "REMOTE_RESET", Args::None),
+    (0x11, "SET_ZONE_START", Args::Value),
+    (0x13, "SET_ZONE_END", Args::Value),
+    (0x15, "WRITE_CHAR", Args::Char),
+    (0x17, "CLEAR_SCREEN", Args::None),
+    (0x19, "ALARM_ACK", Args::Value),
+    (0x1B, "ZONE_SCAN_ON", Args::None),
+    (0x1D, "ZONE_SCAN_OFF", Args::None),
+    (0x1F, "PATTERN_START", Args::Value),
+    (0x21, "PATTERN_STOP", Args::Value),
+    (0x23, "RUN_PATTERN", Args::Value),
+    (0x25, "ZOOM_SPEED", Args::Value),
+    (0x27, "FOCUS_SPEED", Args::Value),
+    (0x29, "RESET_CAMERA", Args::None),
+    (0x2B, "AUTO_FOCUS", Args::Value),
+    (0x2D, "AUTO_IRIS", Args::Value),
+    (0x2F, "AGC", Args::Value),
+    (0x31, "BLC", Args::Value),
+    (0x33, "AWB", Args::Value),
+    (0x35, "PHASE_DELAY_MODE", Args::None),
+    (0x37, "SHUTTER", Args::Word),
+    (0x39, "LINE_LOCK_PHASE", Args::Adjust),
+    (0x3B, "WB_RB", Args::Adjust),
+    (0x3D, "WB_MG", Args::Adjust),
+    (0x3F, "GAIN", Args::Adjust),
+    (0x41, "AUTO_IRIS_LEVEL", Args::Adjust),
+    (0x43, "AUTO_IRIS_PEAK", Args::Adjust),
+    (0x45, "QUERY", Args::None),
+    (0x4B, "PAN_POSITION", Args::Word),
+];
+
+impl fmt::Display for Message {
+    /// Mnemonic format of the message:
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// # fn example() -> Result<()> {
+    /// let msg = Message::go_to_preset(10, 5)?;
+    /// assert_eq!("addr=10 GOTO_PRESET 5", msg.to_string());
+    ///
+    /// let msg = MessageBuilder::new(3)?
+    ///     .left()
+    ///     .pan(Speed::Range(0.5))
+    ///     .up()
+    ///     .tilt(Speed::Range(0.25))
+    ///     .zoom_in()
+    ///     .finalize()?;
+    /// assert_eq!("addr=3 PAN LEFT 0x20 TILT UP 0x10 ZOOM TELE", msg.to_string());
+    /// # Ok(())}
+    /// # example().unwrap();
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match mnemonic(self.as_ref()) {
+            Some(text) => f.write_str(&text),
+            None => write!(f, "RAW {:X}", self),
+        }
+    }
+}
+
+impl fmt::UpperHex for Message {
+    /// Hexadecimal format of the message:
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// let msg = Message::go_to_preset(10, 5).unwrap();
+    /// assert_eq!("FF 0A 00 07 00 05 16", format!("{:X}", msg));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&hex(self.as_ref()))
+    }
+}
+
+impl FromStr for Message {
+    type Err = Error;
+
+    /// Parse a message in mnemonic or hexadecimal format. Keywords are case
+    /// insensitive, and numbers can be decimal or hexadecimal with the `0x`
+    /// prefix.
+    ///
+    /// ```rust
+    /// # use pelcodrs::*;
+    /// # fn example() -> Result<()> {
+    /// let msg: Message = "addr=10 GOTO_PRESET 5".parse()?;
+    /// assert_eq!(Message::go_to_preset(10, 5)?, msg);
+    ///
+    /// let msg: Message = "FF 0A 00 07 00 05 16".parse()?;
+    /// assert_eq!(Message::go_to_preset(10, 5)?, msg);
+    /// # Ok(())}
+    /// # example().unwrap();
+    /// ```
+    fn from_str(s: &str) -> Result<Message> {
+        let tokens: Vec<String> = s.split_whitespace().map(|t| t.to_uppercase()).collect();
+        match tokens.first() {
+            None => Err(parse_error("Empty message")),
+            Some(first) if first.starts_with("ADDR=") => {
+                let address = parse_number(&first["ADDR=".len()..])?;
+                parse_mnemonic(address, &tokens[1..])
+            }
+            Some(first) if first == "RAW" => parse_hex(&tokens[1..]),
+            Some(_) => parse_hex(&tokens),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Message {
+    /// Serialized as a string in hexadecimal format, like
+    /// `"FF 0A 00 07 00 05 16"`.
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:X}", self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Message {
+    /// Deserialized from a string in any format accepted by `FromStr`.
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Message, D::Error> {
+        let text = <String as serde::Deserialize>::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Bytes in hexadecimal separated by spaces, like the frames of the
+/// `{:X}` format of `Message`.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_error(description: &str) -> Error {
+    Error::new(ErrorKind::Parse, description)
+}
+
+/// Name of the command of a message: the extended command name, or `STOP` or
+/// `STANDARD` for the standard commands.
+pub(crate) fn command_name(message: &Message) -> &'static str {
+    let bytes = message.as_ref();
+    if bytes[3] & 1 == 1 {
+        EXTENDED
+            .iter()
+            .find(|(opcode, _, _)| *opcode == bytes[3])
+            .map(|(_, name, _)| *name)
+            .unwrap_or("EXTENDED")
+    } else if bytes[2..6].iter().all(|&b| b == 0) {
+        "STOP"
+    } else {
+        "STANDARD"
+    }
+}
+
+fn mnemonic(bytes: &[u8]) -> Option<String> {
+    if bytes[0] != SYNC_BYTE || checksum(&bytes[1..6]) != bytes[6] {
+        return None;
+    }
+
+    let mut tokens = vec![format!("addr={}", bytes[1])];
+    if bytes[3] & 1 == 1 {
+        extended_tokens(bytes, &mut tokens);
+    } else {
+        standard_tokens(bytes, &mut tokens)?;
+    }
+    Some(tokens.join(" "))
+}
+
+fn standard_tokens(bytes: &[u8], tokens: &mut Vec<String>) -> Option<()> {
+    let cmd1 = Command1::from_bits(bytes[2])?;
+    let cmd2 = Command2::from_bits(bytes[3])?;
+    let (data1, data2) = (bytes[4], bytes[5]);
+    let sense = cmd1.contains(Command1::SENSE);
+    let mut push = |token: &str| tokens.push(String::from(token));
+
+    if cmd1.contains(Command1::CAMERA_ON_OFF) {
+        push(if sense { "CAMERA ON" } else { "CAMERA OFF" });
+    }
+    if cmd1.contains(Command1::AUTO_MANUAL_SCAN) {
+        push(if sense { "SCAN AUTO" } else { "SCAN MANUAL" });
+    }
+    if sense && !cmd1.intersects(Command1::CAMERA_ON_OFF | Command1::AUTO_MANUAL_SCAN) {
+        push("SENSE");
+    }
+
+    if cmd2.intersects(Command2::LEFT | Command2::RIGHT) || data1 != 0 {
+        push("PAN");
+        if cmd2.contains(Command2::LEFT) {
+            push("LEFT");
+        }
+        if cmd2.contains(Command2::RIGHT) {
+            push("RIGHT");
+        }
+        push(&format!("0x{:02X}", data1));
+    }
+    if cmd2.intersects(Command2::UP | Command2::DOWN) || data2 != 0 {
+        push("TILT");
+        if cmd2.contains(Command2::UP) {
+            push("UP");
+        }
+        if cmd2.contains(Command2::DOWN) {
+            push("DOWN");
+        }
+        push(&format!("0x{:02X}", data2));
+    }
+
+    if cmd2.contains(Command2::ZOOM_TELE) {
+        push("ZOOM TELE");
+    }
+    if cmd2.contains(Command2::ZOOM_WIDE) {
+        push("ZOOM WIDE");
+    }
+    if cmd1.contains(Command1::FOCUS_NEAR) {
+        push("FOCUS NEAR");
+    }
+    if cmd2.contains(Command2::FOCUS_FAR) {
+        push("FOCUS FAR");
+    }
+    if cmd1.contains(Command1::IRIS_OPEN) {
+        push("IRIS OPEN");
+    }
+    if cmd1.contains(Command1::IRIS_CLOSE) {
+        push("IRIS CLOSE");
+    }
+
+    if tokens.len() == 1 {
+        tokens.push(String::from("STOP"));
+    }
+    Some(())
+}
+
+fn extended_tokens(bytes: &[u8], tokens: &mut Vec<String>) {
+    let (word1, opcode, data1, data2) = (bytes[2], bytes[3], bytes[4], bytes[5]);
+    let entry = EXTENDED.iter().find(|(op, _, _)| *op == opcode);
+    let word = u16::from_be_bytes([data1, data2]);
+
+    let args = match entry {
+        Some((_, _, Args::None)) if word1 == 0 && word == 0 => Some(vec![]),
+        Some((_, _, Args::Value)) if word1 == 0 && data1 == 0 => Some(vec![data2.to_string()]),
+        Some((_, _, Args::Word)) if word1 == 0 => Some(vec![word.to_string()]),
+        Some((_, _, Args::Char)) if word1 == 0 => {
+            Some(vec![data1.to_string(), format!("0x{:02X}", data2)])
+        }
+        Some((_, _, Args::Adjust)) if word1 == 0 => {
+            Some(vec![String::from("NEW"), word.to_string()])
+        }
+        Some((_, _, Args::Adjust)) if word1 == 1 => {
+            Some(vec![String::from("DELTA"), (word as i16).to_string()])
+        }
+        _ => None,
+    };
+
+    match (entry, args) {
+        (Some((_, name, _)), Some(args)) => {
+            tokens.push(String::from(*name));
+            tokens.extend(args);
+        }
+        _ => {
+            tokens.push(String::from("EXT"));
+            for byte in [opcode, word1, data1, data2].iter() {
+                tokens.push(format!("0x{:02X}", byte));
+            }
+        }
+    }
+}
+
+fn parse_hex(tokens: &[String]) -> Result<Message> {
+    if tokens.len() != 7 {
+        return Err(parse_error("Expected 7 hexadecimal bytes"));
+    }
+    let mut bytes = [0u8; 7];
+    for (byte, token) in bytes.iter_mut().zip(tokens.iter()) {
+        let digits = token.strip_prefix("0X").unwrap_or(token);
+        if digits.is_empty() || digits.len() > 2 {
+            return Err(parse_error("Invalid hexadecimal byte"));
+        }
+        *byte =
+            u8::from_str_radix(digits, 16).map_err(|_| parse_error("Invalid hexadecimal byte"))?;
+    }
+    Ok(Message::from(bytes))
+}
+
+fn parse_mnemonic(address: u8, tokens: &[String]) -> Result<Message> {
+    let first = match tokens.first() {
+        Some(first) => first.as_str(),
+        None => return Err(parse_error("Missing command")),
+    };
+    let args = &tokens[1..];
+
+    if first == "EXT" {
+        let [opcode, word1, data1, data2] = match args {
+            [a, b, c, d] => [
+                parse_number::<u8>(a)?,
+                parse_number(b)?,
+                parse_number(c)?,
+                parse_number(d)?,
+            ],
+            _ => return Err(parse_error("EXT expects 4 bytes")),
+        };
+        return Ok(Message::from_bytes(address, [word1, opcode, data1, data2]));
+    }
+
+    match EXTENDED.iter().find(|(_, name, _)| *name == first) {
+        Some((opcode, _, layout)) => {
+            let words = extended_words(*layout, args)?;
+            Ok(Message::from_bytes(
+                address,
+                [words[0], *opcode, words[1], words[2]],
+            ))
+        }
+        None => parse_standard(address, tokens),
+    }
+}
+
+// Word 1, data 1 and data 2 of an extended command
+fn extended_words(layout: Args, args: &[String]) -> Result<[u8; 3]> {
+    let words = match (layout, args) {
+        (Args::None, []) => [0, 0, 0],
+        (Args::Value, [value]) => [0, 0, parse_number(value)?],
+        (Args::Word, [value]) => {
+            let bytes = parse_number::<u16>(value)?.to_be_bytes();
+            [0, bytes[0], bytes[1]]
+        }
+        (Args::Char, [column, character]) => [0, parse_number(column)?, parse_number(character)?],
+        (Args::Adjust, [kind, value]) if kind == "NEW" => {
+            let bytes = parse_number::<u16>(value)?.to_be_bytes();
+            [0, bytes[0], bytes[1]]
+        }
+        (Args::Adjust, [kind, value]) if kind == "DELTA" => {
+            let bytes = parse_number::<i16>(value)?.to_be_bytes();
+            [1, bytes[0], bytes[1]]
+        }
+        _ => return Err(parse_error("Invalid arguments")),
+    };
+    Ok(words)
+}
+
+fn parse_standard(address: u8, tokens: &[String]) -> Result<Message> {
+    let mut cmd1 = Command1::empty();
+    let mut cmd2 = Command2::empty();
+    let (mut data1, mut data2) = (0, 0);
+
+    let mut tokens = tokens.iter().map(|t| t.as_str()).peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            "STOP" => (),
+            "SENSE" => cmd1 |= Command1::SENSE,
+            "PAN" | "TILT" => {
+                while let Some(&direction) = tokens.peek() {
+                    match (token, direction) {
+                        ("PAN", "LEFT") => cmd2 |= Command2::LEFT,
+                        ("PAN", "RIGHT") => cmd2 |= Command2::RIGHT,
+                        ("TILT", "UP") => cmd2 |= Command2::UP,
+                        ("TILT", "DOWN") => cmd2 |= Command2::DOWN,
+                        _ => break,
+                    }
+                    tokens.next();
+                }
+                if let Some(speed) = tokens.peek().and_then(|t| parse_number(t).ok()) {
+                    tokens.next();
+                    if token == "PAN" {
+                        data1 = speed;
+                    } else {
+                        data2 = speed;
+                    }
+                }
+            }
+            _ => {
+                let value = tokens.next().unwrap_or("");
+                match (token, value) {
+                    ("CAMERA", "ON") => cmd1 |= Command1::SENSE | Command1::CAMERA_ON_OFF,
+                    ("CAMERA", "OFF") => cmd1 |= Command1::CAMERA_ON_OFF,
+                    ("SCAN", "AUTO") => cmd1 |= Command1::SENSE | Command1::AUTO_MANUAL_SCAN,
+                    ("SCAN", "MANUAL") => cmd1 |= Command1::AUTO_MANUAL_SCAN,
+                    ("ZOOM", "TELE") => cmd2 |= Command2::ZOOM_TELE,
+                    ("ZOOM", "WIDE") => cmd2 |= Command2::ZOOM_WIDE,
+                    ("FOCUS", "NEAR") => cmd1 |= Command1::FOCUS_NEAR,
+                    ("FOCUS", "FAR") => cmd2 |= Command2::FOCUS_FAR,
+                    ("IRIS", "OPEN") => cmd1 |= Command1::IRIS_OPEN,
+                    ("IRIS", "CLOSE") => cmd1 |= Command1::IRIS_CLOSE,
+                    _ => return Err(parse_error(&format!("Unknown command {} {}", token, value))),
+                }
+            }
+        }
+    }
+
+    Ok(Message::new(address, cmd1, cmd2, data1, data2))
+}
+
+trait Number: Sized {
+    fn from_str_radix(digits: &str, radix: u32) -> std::result::Result<Self, ()>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_str_radix(digits: &str, radix: u32) -> std::result::Result<Self, ()> {
+                <$t>::from_str_radix(digits, radix).map_err(|_| ())
+            }
+        }
+    )*};
+}
+
+impl_number!(u8, u16, i16);
+
+fn parse_number<N: Number>(token: &str) -> Result<N> {
+    let upper = token.to_uppercase();
+    let result = match upper.strip_prefix("0X") {
+        Some(digits) => N::from_str_radix(digits, 16),
+        None => N::from_str_radix(&upper, 10),
+    };
+    result.map_err(|_| parse_error(&format!("Invalid number {}", token)))
+}
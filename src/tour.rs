@@ -0,0 +1,304 @@
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Single step of a [Tour](struct.Tour.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TourStep {
+    /// Call a preset.
+    GoToPreset(u8),
+    /// Wait before running the next step.
+    Dwell(Duration),
+    /// Send any message, for example a zoom or focus adjustment, or an
+    /// auxiliary action.
+    Send(Message),
+}
+
+/// Progress report of a running [Tour](struct.Tour.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TourEvent {
+    /// The step at the given index was run.
+    Step(usize, TourStep),
+    /// The last step was run, the given number of cycles are complete.
+    CycleCompleted(u64),
+    /// The tour is paused for operator activity.
+    Paused,
+    /// The tour resumed after the idle timeout.
+    Resumed,
+}
+
+/// Cyclic sequence of presets and actions run on a device.
+///
+/// The tour is a state machine driven by
+/// [Tour::tick()](struct.Tour.html#method.tick) with the current time, which
+/// makes it testable with any clock. It can also be run in a background thread
+/// with [Tour::spawn()](struct.Tour.html#method.spawn).
+///
+/// When an operator takes control of the camera, the tour must be notified
+/// with [Tour::operator_activity()](struct.Tour.html#method.operator_activity).
+/// It is then paused until no activity occurred for the idle timeout, and it
+/// resumes by going back to the last called preset.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # use std::time::Duration;
+/// # fn example() -> Result<()> {
+/// let mut tour = Tour::new(
+///     10,
+///     vec![
+///         TourStep::GoToPreset(1),
+///         TourStep::Dwell(Duration::from_secs(5)),
+///         TourStep::GoToPreset(2),
+///         TourStep::Dwell(Duration::from_secs(5)),
+///     ],
+/// )?;
+///
+/// let mut port = PelcoDPort::new(std::io::Cursor::new(Vec::new()));
+/// let events = tour.tick(&mut port, Duration::from_secs(0))?;
+/// assert_eq!(TourEvent::Step(0, TourStep::GoToPreset(1)), events[0]);
+/// assert_eq!(Duration::from_secs(5), tour.next_deadline());
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tour {
+    address: u8,
+    steps: Vec<TourStep>,
+    idle_timeout: Duration,
+    index: usize,
+    next_at: Duration,
+    last_preset: Option<usize>,
+    last_activity: Option<Duration>,
+    cycles: u64,
+}
+
+impl Tour {
+    /// New tour for the device at `address`. An error is returned if there
+    /// are no steps, if a preset is invalid, or if the total dwell time is
+    /// zero, since such a tour would flood the bus with frames.
+    pub fn new(address: u8, steps: Vec<TourStep>) -> Result<Tour> {
+        if steps.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidValue, "Empty tour"));
+        }
+        let dwell: Duration = steps
+            .iter()
+            .map(|step| match step {
+                TourStep::Dwell(duration) => *duration,
+                _ => Duration::from_secs(0),
+            })
+            .sum();
+        if dwell == Duration::from_secs(0) {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                "Tour without dwell time",
+            ));
+        }
+        for step in steps.iter() {
+            if let TourStep::GoToPreset(preset_id) = step {
+                Message::go_to_preset(address, *preset_id)?;
+            }
+        }
+        Ok(Tour {
+            address,
+            steps,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            index: 0,
+            next_at: Duration::from_secs(0),
+            last_preset: None,
+            last_activity: None,
+            cycles: 0,
+        })
+    }
+
+    /// Set the time without operator activity after which a paused tour
+    /// resumes. The default is 30 seconds.
+    pub fn set_idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Address of the device.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Steps of the tour.
+    pub fn steps(&self) -> &[TourStep] {
+        &self.steps
+    }
+
+    /// Whether the tour is paused for operator activity.
+    pub fn is_paused(&self) -> bool {
+        self.last_activity.is_some()
+    }
+
+    /// Time at which the tour has something to do.
+    pub fn next_deadline(&self) -> Duration {
+        match self.last_activity {
+            Some(activity) => activity + self.idle_timeout,
+            None => self.next_at,
+        }
+    }
+
+    /// Notify operator activity at time `now`, which pauses the tour.
+    /// `TourEvent::Paused` is returned if the tour was running.
+    pub fn operator_activity(&mut self, now: Duration) -> Option<TourEvent> {
+        let was_paused = self.is_paused();
+        self.last_activity = Some(now);
+        if was_paused {
+            None
+        } else {
+            Some(TourEvent::Paused)
+        }
+    }
+
+    /// Run the steps due at time `now`, and return the corresponding events.
+    pub fn tick<T: Read + Write>(
+        &mut self,
+        port: &mut PelcoDPort<T>,
+        now: Duration,
+    ) -> Result<Vec<TourEvent>> {
+        let mut events = Vec::new();
+
+        if let Some(activity) = self.last_activity {
+            if now < activity + self.idle_timeout {
+                return Ok(events);
+            }
+            self.last_activity = None;
+            self.index = self.last_preset.unwrap_or(self.index);
+            self.next_at = now;
+            events.push(TourEvent::Resumed);
+        }
+
+        // At most one cycle per tick
+        for _ in 0..self.steps.len() {
+            if now < self.next_at {
+                break;
+            }
+
+            let index = self.index;
+            let step = self.steps[index];
+            match step {
+                TourStep::GoToPreset(preset_id) => {
+                    port.send_message(Message::go_to_preset(self.address, preset_id)?)?;
+                    self.last_preset = Some(index);
+                }
+                TourStep::Dwell(duration) => self.next_at = now + duration,
+                TourStep::Send(message) => port.send_message(message)?,
+            }
+            events.push(TourEvent::Step(index, step));
+
+            self.index = (index + 1) % self.steps.len();
+            if self.index == 0 {
+                self.cycles += 1;
+                events.push(TourEvent::CycleCompleted(self.cycles));
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Run the tour in a background thread, using `port` and its clock.
+    pub fn spawn<T>(self, port: PelcoDPort<T>) -> TourHandle<T>
+    where
+        T: Read + Write + Send + 'static,
+    {
+        let (control_tx, control_rx) = mpsc::channel();
+        let (events_tx, events_rx) = mpsc::channel();
+        let thread = std::thread::spawn(move || run_tour(self, port, control_rx, events_tx));
+        TourHandle {
+            control: control_tx,
+            events: events_rx,
+            thread,
+        }
+    }
+}
+
+enum Control {
+    Operator(Option<Message>),
+    Stop,
+}
+
+/// Handle on a [Tour](struct.Tour.html) running in a background thread.
+///
+/// The port is owned by the thread while the tour runs, so the operator
+/// commands must be sent with
+/// [TourHandle::send_operator_message()](struct.TourHandle.html#method.send_operator_message),
+/// which also pauses the tour.
+#[derive(Debug)]
+pub struct TourHandle<T: Read + Write> {
+    control: Sender<Control>,
+    events: Receiver<TourEvent>,
+    thread: JoinHandle<Result<PelcoDPort<T>>>,
+}
+
+impl<T: Read + Write> TourHandle<T> {
+    /// Notify operator activity, which pauses the tour.
+    pub fn operator_activity(&self) {
+        let _ = self.control.send(Control::Operator(None));
+    }
+
+    /// Send an operator command to the device, and pause the tour.
+    pub fn send_operator_message(&self, message: Message) {
+        let _ = self.control.send(Control::Operator(Some(message)));
+    }
+
+    /// Receiver of the progress events of the tour.
+    pub fn events(&self) -> &Receiver<TourEvent> {
+        &self.events
+    }
+
+    /// Stop the tour and get the port back. The error which stopped the tour
+    /// early, if any, is returned.
+    ///
+    /// # Panics
+    ///
+    /// If the tour thread panicked, the panic is propagated to the caller.
+    pub fn stop(self) -> Result<PelcoDPort<T>> {
+        let _ = self.control.send(Control::Stop);
+        match self.thread.join() {
+            Ok(result) => result,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+}
+
+fn run_tour<T: Read + Write>(
+    mut tour: Tour,
+    mut port: PelcoDPort<T>,
+    control: Receiver<Control>,
+    events: Sender<TourEvent>,
+) -> Result<PelcoDPort<T>> {
+    loop {
+        loop {
+            match control.try_recv() {
+                Ok(Control::Operator(message)) => {
+                    if let Some(event) = tour.operator_activity(port.clock().now()) {
+                        let _ = events.send(event);
+                    }
+                    if let Some(message) = message {
+                        port.send_message(message)?;
+                    }
+                }
+                Ok(Control::Stop) | Err(TryRecvError::Disconnected) => return Ok(port),
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        let now = port.clock().now();
+        for event in tour.tick(&mut port, now)? {
+            let _ = events.send(event);
+        }
+
+        let wait = tour.next_deadline().saturating_sub(port.clock().now());
+        port.clock().sleep(wait.min(POLL_INTERVAL));
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::error::*;
+use crate::message::Message;
+use crate::port::PelcoDPort;
+
+const DEFAULT_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Position of a zone boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneEdge {
+    /// Position stored in a preset.
+    Preset(u8),
+    /// Absolute pan position, in hundredths of degree.
+    PanPosition(u16),
+}
+
+impl ZoneEdge {
+    fn move_message(self, address: u8) -> Result<Message> {
+        match self {
+            ZoneEdge::Preset(preset_id) => Message::go_to_preset(address, preset_id),
+            ZoneEdge::PanPosition(position) => Message::set_pan_position(address, position),
+        }
+    }
+}
+
+/// Zone configuration workflow.
+///
+/// Configuring a zone requires moving the camera to each boundary before
+/// recording it, then optionally labeling the zone on screen and enabling the
+/// zone scan. [Zone::plan()](struct.Zone.html#method.plan) returns the
+/// messages of the workflow for review, and
+/// [Zone::apply()](struct.Zone.html#method.apply) sends them, waiting for the
+/// camera to reach each boundary.
+///
+/// ```rust
+/// # use pelcodrs::*;
+/// # fn example() -> Result<()> {
+/// let mut zone = Zone::new(1, ZoneEdge::Preset(10), ZoneEdge::PanPosition(9000));
+/// zone.set_label("GATE").set_scan(true);
+///
+/// let plan = zone.plan(3)?;
+/// assert_eq!(Message::go_to_preset(3, 10)?, plan[0]);
+/// assert_eq!(Message::set_zone_start(3, 1)?, plan[1]);
+/// assert_eq!(Message::set_pan_position(3, 9000)?, plan[2]);
+/// assert_eq!(Message::set_zone_end(3, 1)?, plan[3]);
+/// assert_eq!(Message::zone_scan_on(3)?, *plan.last().unwrap());
+/// # Ok(())}
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    id: u8,
+    start: ZoneEdge,
+    end: ZoneEdge,
+    label: Option<String>,
+    scan: Option<bool>,
+    settle_time: Duration,
+}
+
+impl Zone {
+    pub fn new(id: u8, start: ZoneEdge, end: ZoneEdge) -> Zone {
+        Zone {
+            id,
+            start,
+            end,
+            label: None,
+            scan: None,
+            settle_time: DEFAULT_SETTLE_TIME,
+        }
+    }
+
+    /// Zone number.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Label written on screen after recording the boundaries. It must
+    /// contain only ASCII characters.
+    pub fn set_label(&mut self, label: &str) -> &mut Self {
+        self.label = Some(String::from(label));
+        self
+    }
+
+    /// Turn the zone scan on or off at the end of the workflow.
+    pub fn set_scan(&mut self, on: bool) -> &mut Self {
+        self.scan = Some(on);
+        self
+    }
+
+    /// Time given to the camera for reaching a boundary. The default is 2
+    /// seconds.
+    pub fn set_settle_time(&mut self, settle_time: Duration) -> &mut Self {
+        self.settle_time = settle_time;
+        self
+    }
+
+    /// Messages of the workflow for the device at `address`, without sending
+    /// them.
+    pub fn plan(&self, address: u8) -> Result<Vec<Message>> {
+        Ok(self
+            .steps(address)?
+            .into_iter()
+            .map(|(msg, _)| msg)
+            .collect())
+    }
+
+    /// Send the messages of the workflow to the device at `address`, waiting
+    /// with the clock of the port. The messages are all checked before
+    /// sending the first one.
+    pub fn apply<T: Read + Write>(&self, port: &mut PelcoDPort<T>, address: u8) -> Result<()> {
+        for (message, settle) in self.steps(address)? {
+            port.send_message(message)?;
+            if settle {
+                port.clock().sleep(self.settle_time);
+            }
+        }
+        Ok(())
+    }
+
+    // Messages, with whether the camera must settle after sending them
+    fn steps(&self, address: u8) -> Result<Vec<(Message, bool)>> {
+        let mut steps = vec![
+            (self.start.move_message(address)?, true),
+            (Message::set_zone_start(address, self.id)?, false),
+            (self.end.move_message(address)?, true),
+            (Message::set_zone_end(address, self.id)?, false),
+        ];
+
+        if let Some(label) = self.label.as_ref() {
+            if label.len() > usize::from(u8::MAX) {
+                return Err(Error::new(ErrorKind::InvalidValue, "Label too long"));
+            }
+            for (column, character) in label.chars().enumerate() {
+                let msg = Message::write_char_to_screen(address, column as u8, character)?;
+                steps.push((msg, false));
+            }
+        }
+
+        match self.scan {
+            Some(true) => steps.push((Message::zone_scan_on(address)?, false)),
+            Some(false) => steps.push((Message::zone_scan_off(address)?, false)),
+            None => (),
+        }
+
+        Ok(steps)
+    }
+}
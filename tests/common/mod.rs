@@ -0,0 +1,42 @@
+//! Helpers shared by the integration tests, which simulate the devices with
+//! the mock transport of the `testing` feature.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+/// Port on a mock device, both using `clock`. The mock records the time at
+/// which each frame is received.
+pub fn timed_port(clock: &ManualClock) -> (PelcoDPort<MockTransport>, MockTransport) {
+    let mock = MockTransport::new();
+    mock.set_clock(clock.clone());
+    let mut port = PelcoDPort::new(mock.clone());
+    port.set_clock(clock.clone());
+    (port, mock)
+}
+
+/// Frames received by the mock since the last call, with their time.
+pub fn received(mock: &MockTransport) -> Vec<(Duration, Message)> {
+    let sent = mock.sent_at();
+    mock.clear_sent();
+    sent
+}
+
+/// Frames received by the mock since the last call, without their time.
+pub fn messages(mock: &MockTransport) -> Vec<Message> {
+    received(mock).into_iter().map(|(_, m)| m).collect()
+}
+
+pub fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+pub fn stop(address: u8) -> Message {
+    MessageBuilder::new(address)
+        .unwrap()
+        .stop()
+        .finalize()
+        .unwrap()
+}
@@ -0,0 +1,152 @@
+use std::convert::TryFrom;
+use std::io::Cursor;
+use std::num::NonZeroU8;
+use std::time::Duration;
+
+use pelcodrs::*;
+
+#[test]
+fn test_address_byte() {
+    assert_eq!(0x00, Address::Broadcast.byte());
+    assert_eq!(0x0A, u8::from(Address::try_from(10).unwrap()));
+    assert!(Address::Broadcast.is_broadcast());
+    assert!(Address::try_from(0xFF).unwrap().is_broadcast());
+    assert!(!Address::try_from(0xFE).unwrap().is_broadcast());
+
+    let msg = Message::go_to_preset(10, 1).unwrap();
+    assert_eq!(
+        &[0xFF, 0x00, 0x00, 0x07, 0x00, 0x01, 0x08],
+        msg.with_address(Address::Broadcast).as_ref()
+    );
+    assert_eq!(
+        Message::query().unwrap(),
+        Message::from_bytes(10, [0x00, 0x45, 0x00, 0x00]).with_address(Address::Broadcast)
+    );
+}
+
+#[test]
+fn test_broadcast_safe() {
+    assert!(Message::go_to_preset(1, 1).unwrap().is_broadcast_safe());
+    assert!(MessageBuilder::new(1)
+        .unwrap()
+        .stop()
+        .finalize()
+        .unwrap()
+        .is_broadcast_safe());
+    assert!(Message::set_auxiliary(1, 0, 1).unwrap().is_broadcast_safe());
+
+    assert!(!Message::set_preset(1, 1).unwrap().is_broadcast_safe());
+    assert!(!Message::remote_reset(1).unwrap().is_broadcast_safe());
+    assert!(!Message::query().unwrap().is_broadcast_safe());
+    assert!(!Message::from_bytes(1, [0x00, 0x5F, 0x00, 0x00]).is_broadcast_safe());
+}
+
+#[test]
+fn test_broadcast() {
+    let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+    port.broadcast(Message::go_to_preset(10, 1).unwrap())
+        .unwrap();
+    assert_eq!(
+        &[0xFF, 0x00, 0x00, 0x07, 0x00, 0x01, 0x08],
+        &port.get_ref().get_ref()[..]
+    );
+
+    let error = port
+        .broadcast(Message::set_preset(10, 1).unwrap())
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidValue));
+    assert_eq!(7, port.get_ref().get_ref().len());
+
+    port.set_broadcast_address(Address::try_from(0xFF).unwrap());
+    port.broadcast(Message::go_to_preset(10, 1).unwrap())
+        .unwrap();
+    assert_eq!(
+        &[0xFF, 0xFF, 0x00, 0x07, 0x00, 0x01, 0x07],
+        &port.get_ref().get_ref()[7..]
+    );
+}
+
+#[test]
+fn test_send_to_all() {
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+    port.set_clock(clock.clone()).set_rate_limit(RateLimit {
+        min_gap: Duration::from_millis(10),
+        ..RateLimit::default()
+    });
+
+    let preset = Message::go_to_preset(1, 1).unwrap();
+    let count = port.send_to_all(preset, 3..=5).unwrap();
+    assert_eq!(3, count);
+
+    let sent: Vec<Message> = port
+        .get_ref()
+        .get_ref()
+        .chunks(7)
+        .map(|chunk| Message::try_from(chunk).unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            Message::go_to_preset(3, 1).unwrap(),
+            Message::go_to_preset(4, 1).unwrap(),
+            Message::go_to_preset(5, 1).unwrap(),
+        ],
+        sent
+    );
+    assert_eq!(Duration::from_millis(20), clock.now());
+}
+
+#[test]
+fn test_address_validation() {
+    let unit = Address::try_from(10).unwrap();
+    assert_eq!(Address::Unit(NonZeroU8::new(10).unwrap()), unit);
+    assert_eq!("10", unit.to_string());
+    assert_eq!("broadcast", Address::Broadcast.to_string());
+
+    let error = Address::try_from(0).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidValue));
+
+    // Typed and u8 addresses build the same messages, but 0 is rejected
+    assert_eq!(
+        Message::go_to_preset(10, 5).unwrap(),
+        Message::go_to_preset(unit, 5).unwrap()
+    );
+    assert!(Message::go_to_preset(0, 5).is_err());
+    assert!(Message::remote_reset(0).is_err());
+    assert_eq!(
+        &[0xFF, 0x00, 0x00, 0x17, 0x00, 0x00, 0x17],
+        Message::clear_screen(Address::Broadcast).unwrap().as_ref()
+    );
+
+    assert!(MessageBuilder::new(0).is_err());
+    assert_eq!(
+        MessageBuilder::new(10).unwrap().up().finalize().unwrap(),
+        MessageBuilder::new(unit).unwrap().up().finalize().unwrap()
+    );
+    assert_eq!(
+        0,
+        MessageBuilder::new(Address::Broadcast)
+            .unwrap()
+            .stop()
+            .finalize()
+            .unwrap()
+            .address()
+    );
+}
+
+#[test]
+fn test_send_to_all_invalid_address() {
+    let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+    let preset = Message::go_to_preset(1, 1).unwrap();
+    assert!(port.send_to_all(preset, 0..=2).is_err());
+    assert!(port.send_to_all(preset, vec![1, 2, 0, 3]).is_err());
+    assert!(port.get_ref().get_ref().is_empty());
+
+    // 0xFF is a broadcast address, checked like a broadcast
+    let error = port
+        .send_to_all(Message::set_preset(1, 1).unwrap(), vec![1, 0xFF])
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidValue));
+    assert!(port.get_ref().get_ref().is_empty());
+    assert_eq!(2, port.send_to_all(preset, vec![1, 0xFF]).unwrap());
+}
@@ -0,0 +1,148 @@
+use std::io::ErrorKind as IoErrorKind;
+use std::sync::mpsc;
+
+use pelcodrs::testing::{Failure, MockTransport};
+use pelcodrs::*;
+
+fn new_port_and_device() -> (PelcoDPort<MockTransport>, MockTransport) {
+    let device = MockTransport::new();
+    (PelcoDPort::new(device.clone()), device)
+}
+
+fn general_response(address: u8, alarms: u8) -> [u8; 4] {
+    [0xFF, address, alarms, checksum(&[address, alarms])]
+}
+
+fn raised(address: u8, alarm: u8) -> AlarmEvent {
+    AlarmEvent {
+        address,
+        alarm,
+        state: AlarmState::Raised,
+    }
+}
+
+fn cleared(address: u8, alarm: u8) -> AlarmEvent {
+    AlarmEvent {
+        address,
+        alarm,
+        state: AlarmState::Cleared,
+    }
+}
+
+#[test]
+fn test_alarm_edges() {
+    let mut monitor = AlarmMonitor::new();
+    assert_eq!(vec![raised(1, 1), raised(1, 8)], monitor.update(1, 0x81));
+    assert!(monitor.update(1, 0x81).is_empty());
+    assert_eq!(vec![cleared(1, 1), raised(1, 2)], monitor.update(1, 0x82));
+    assert_eq!(vec![raised(2, 3)], monitor.update(2, 0x04));
+    assert!(monitor.is_raised(1, 8));
+    assert!(!monitor.is_raised(1, 1));
+    assert_eq!(0x04, monitor.alarms(2));
+}
+
+#[test]
+fn test_alarm_listen_actions_and_acknowledge() {
+    let (mut port, device) = new_port_and_device();
+    let (sender, receiver) = mpsc::channel();
+    let mut monitor = AlarmMonitor::new();
+    monitor
+        .set_auto_acknowledge(true)
+        .set_sender(sender)
+        .set_actions(
+            7,
+            3,
+            vec![
+                Message::go_to_preset(7, 12).unwrap(),
+                Message::set_auxiliary(7, 0, 1).unwrap(),
+            ],
+        );
+
+    device
+        .unsolicited(&general_response(7, 0x00))
+        .unsolicited(&[0xFF, 7, 0x04, 0x00]) // corrupted
+        .unsolicited(&general_response(7, 0x04));
+    let events = monitor.listen(&mut port).unwrap();
+    assert_eq!(vec![raised(7, 3)], events);
+    assert_eq!(events, receiver.try_iter().collect::<Vec<_>>());
+
+    assert_eq!(
+        vec![
+            Message::go_to_preset(7, 12).unwrap(),
+            Message::set_auxiliary(7, 0, 1).unwrap(),
+            Message::alarm_acknowledge(7, 3).unwrap(),
+        ],
+        device.sent()
+    );
+
+    device.clear_sent();
+    device.unsolicited(&general_response(7, 0x00));
+    assert_eq!(vec![cleared(7, 3)], monitor.listen(&mut port).unwrap());
+    assert!(device.sent().is_empty());
+}
+
+fn camera_on(address: u8) -> Result<Message> {
+    MessageBuilder::new(address).unwrap().camera_on().finalize()
+}
+
+#[test]
+fn test_alarm_poll() {
+    let (mut port, device) = new_port_and_device();
+    let mut monitor = AlarmMonitor::new();
+    monitor.set_auto_acknowledge(true);
+
+    device
+        .expect(camera_on(1).unwrap())
+        .reply(&general_response(1, 0x00));
+    device
+        .expect(camera_on(2).unwrap())
+        .reply(&general_response(2, 0x01));
+    device.expect(Message::alarm_acknowledge(2, 1).unwrap());
+    device
+        .expect(camera_on(3).unwrap())
+        .reply(&general_response(3, 0x00));
+    let events = monitor.poll(&mut port, 1..=3, camera_on).unwrap();
+    assert_eq!(vec![raised(2, 1)], events);
+    device.verify();
+
+    device
+        .expect(camera_on(2).unwrap())
+        .reply(&general_response(2, 0x00));
+    let events = monitor.poll(&mut port, vec![2], camera_on).unwrap();
+    assert_eq!(vec![cleared(2, 1)], events);
+    device.verify();
+}
+
+#[test]
+fn test_alarm_kept_pending_after_error() {
+    let (mut port, device) = new_port_and_device();
+    let mut monitor = AlarmMonitor::new();
+    monitor.set_auto_acknowledge(true).set_actions(
+        7,
+        3,
+        vec![Message::go_to_preset(7, 12).unwrap()],
+    );
+    let response = Response::General {
+        address: 7,
+        alarms: 0x04,
+    };
+
+    device.inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    let _ = monitor
+        .handle(&mut port, &response)
+        .expect_err("The action should fail");
+    assert!(!monitor.is_raised(7, 3));
+
+    assert_eq!(
+        vec![raised(7, 3)],
+        monitor.handle(&mut port, &response).unwrap()
+    );
+    assert!(monitor.is_raised(7, 3));
+    assert_eq!(
+        vec![
+            Message::go_to_preset(7, 12).unwrap(),
+            Message::alarm_acknowledge(7, 3).unwrap(),
+        ],
+        device.sent()
+    );
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+const DOME: AuxTable = AuxTable::new(
+    "Dome",
+    3,
+    &[
+        (AuxFunction::Wiper, 1),
+        (AuxFunction::Washer, 2),
+        (AuxFunction::IrIlluminator, 3),
+    ],
+);
+
+#[test]
+fn test_aux_table() {
+    assert_eq!(3, DOME.aux_id(AuxFunction::IrIlluminator).unwrap());
+    assert_eq!(3, DOME.aux_id(AuxFunction::Relay(3)).unwrap());
+    let _ = DOME.aux_id(AuxFunction::Heater).expect_err("No heater");
+    let _ = DOME
+        .aux_id(AuxFunction::Relay(0))
+        .expect_err("No auxiliary 0");
+    let _ = DOME
+        .aux_id(AuxFunction::Relay(4))
+        .expect_err("Only 3 auxiliaries");
+    assert_eq!(8, AuxTable::GENERIC.aux_id(AuxFunction::Relay(8)).unwrap());
+}
+
+#[test]
+fn test_aux_set_clear() {
+    let mut port = PelcoDPort::new(MockTransport::new());
+    let mut aux = Auxiliary::new(DOME);
+
+    aux.set(&mut port, 4, AuxFunction::IrIlluminator).unwrap();
+    aux.set(&mut port, 5, AuxFunction::Wiper).unwrap();
+    assert!(aux.is_set(4, AuxFunction::IrIlluminator));
+    assert!(aux.is_set(4, AuxFunction::Relay(3)));
+    assert!(!aux.is_set(4, AuxFunction::Wiper));
+    assert_eq!(vec![3], aux.active(4));
+
+    aux.clear(&mut port, 4, AuxFunction::Relay(3)).unwrap();
+    assert!(!aux.is_set(4, AuxFunction::IrIlluminator));
+
+    aux.clear_all(&mut port).unwrap();
+    assert!(aux.active(5).is_empty());
+
+    let _ = aux
+        .set(&mut port, 4, AuxFunction::Heater)
+        .expect_err("No heater");
+}
+
+#[test]
+fn test_aux_pulse() {
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(MockTransport::new());
+    port.set_clock(clock.clone());
+    let mut aux = Auxiliary::new(DOME);
+
+    aux.pulse(&mut port, 7, AuxFunction::Wiper, Duration::from_secs(2))
+        .unwrap();
+    assert_eq!(Duration::from_secs(2), clock.now());
+    assert!(!aux.is_set(7, AuxFunction::Wiper));
+}
+
+#[test]
+fn test_aux_guard_clears_on_drop() {
+    let mut aux = Auxiliary::new(DOME);
+    let device = MockTransport::new();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut port = PelcoDPort::new(device.clone());
+        let mut guard = aux.hold(&mut port, 9, AuxFunction::Washer).unwrap();
+        guard
+            .port()
+            .send_message(Message::go_to_preset(9, 1).unwrap())
+            .unwrap();
+        panic!("Application crash");
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(
+        vec![
+            Message::set_auxiliary(9, 0, 2).unwrap(),
+            Message::go_to_preset(9, 1).unwrap(),
+            Message::clear_auxiliary(9, 0, 2).unwrap(),
+        ],
+        messages(&device)
+    );
+    assert!(!aux.is_set(9, AuxFunction::Washer));
+}
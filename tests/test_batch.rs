@@ -0,0 +1,67 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+fn osd_messages() -> Vec<Message> {
+    vec![
+        Message::clear_screen(10).unwrap(),
+        Message::write_char_to_screen(10, 0, 'A').unwrap(),
+        Message::go_to_preset(11, 2).unwrap(),
+    ]
+}
+
+#[test]
+fn test_batch_encoding() {
+    let mut batch: MessageBatch = osd_messages().into_iter().collect();
+    assert_eq!(3, batch.len());
+    assert!(!batch.is_empty());
+    assert_eq!(osd_messages(), batch.iter().collect::<Vec<_>>());
+
+    let expected: Vec<u8> = osd_messages()
+        .iter()
+        .flat_map(|m| m.as_ref().to_vec())
+        .collect();
+    assert_eq!(&expected[..], batch.as_ref());
+
+    batch.clear();
+    assert!(batch.is_empty());
+    batch.extend(osd_messages().into_iter().take(1));
+    assert_eq!(
+        vec![Message::clear_screen(10).unwrap()],
+        batch.iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_send_batch_single_write() {
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    let batch: MessageBatch = osd_messages().into_iter().collect();
+    port.send_batch(&batch).unwrap();
+
+    assert_eq!(1, device.writes());
+    assert_eq!(batch.as_ref(), &device.written()[..]);
+
+    let metrics = port.metrics();
+    assert_eq!(3, metrics.frames_sent);
+    assert_eq!(21, metrics.bytes_written);
+    assert_eq!(2, metrics.addresses[&10].frames_sent);
+    assert_eq!(Some(&1), metrics.addresses[&11].commands.get("GOTO_PRESET"));
+}
+
+#[test]
+fn test_send_batch_rate_limited() {
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+    port.set_clock(clock.clone()).set_rate_limit(RateLimit {
+        min_gap: Duration::from_millis(40),
+        ..RateLimit::default()
+    });
+
+    let batch: MessageBatch = osd_messages().into_iter().collect();
+    port.send_batch(&batch).unwrap();
+    assert_eq!(batch.as_ref(), &port.get_ref().get_ref()[..]);
+    assert_eq!(Duration::from_millis(80), clock.now());
+}
@@ -0,0 +1,132 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+fn record_session(clock: &ManualClock) -> String {
+    let capture = CaptureWriter::new(Vec::new(), clock.clone()).unwrap();
+    let (_, device) = timed_port(clock);
+    device
+        .expect(Message::go_to_preset(10, 5).unwrap())
+        .reply(&[0xFF, 0x0A, 0x00, 0x0A]);
+    let mut port = PelcoDPort::new(RecordingTransport::new(device, capture));
+
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    clock.advance(Duration::from_micros(12_500));
+    let response = port.receive_response(ResponseKind::General).unwrap();
+    assert_eq!(
+        Some(Response::General {
+            address: 10,
+            alarms: 0
+        }),
+        response
+    );
+    clock.advance(Duration::from_secs(2));
+    port.send_message(stop(10)).unwrap();
+
+    let (_, capture) = port.into_inner().into_inner();
+    String::from_utf8(capture.into_inner().unwrap()).unwrap()
+}
+
+#[test]
+fn test_capture_recording() {
+    let clock = ManualClock::new();
+    clock.advance(Duration::from_secs(100));
+
+    assert_eq!(
+        "# pelcodrs capture v1\n\
+         0 TX FF 0A 00 07 00 05 16\n\
+         12500 RX FF 0A 00 0A\n\
+         2012500 TX FF 0A 00 00 00 00 0A\n",
+        record_session(&clock)
+    );
+}
+
+#[test]
+fn test_capture_error() {
+    let clock = ManualClock::new();
+    // Room for the header only
+    let mut buffer = [0u8; 22];
+    let capture = CaptureWriter::new(Cursor::new(&mut buffer[..]), clock.clone()).unwrap();
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(RecordingTransport::new(device.clone(), capture));
+
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    port.send_message(stop(10)).unwrap();
+    assert_eq!(2, device.sent().len());
+    let error = port.get_ref().capture_error().unwrap();
+    assert_eq!(std::io::ErrorKind::WriteZero, error.kind());
+}
+
+#[test]
+fn test_capture_reader() {
+    let text = "# pelcodrs capture v1\n\
+                \n\
+                # comment\n\
+                0 TX FF 0A 00 07 00 05 16\n\
+                12500 RX ff 0a 00 0a\n";
+    let records = CaptureReader::new(Cursor::new(text))
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            CaptureRecord {
+                timestamp: Duration::from_secs(0),
+                direction: CaptureDirection::Sent,
+                bytes: vec![0xFF, 0x0A, 0x00, 0x07, 0x00, 0x05, 0x16],
+            },
+            CaptureRecord {
+                timestamp: Duration::from_micros(12_500),
+                direction: CaptureDirection::Received,
+                bytes: vec![0xFF, 0x0A, 0x00, 0x0A],
+            },
+        ],
+        records
+    );
+    assert_eq!("12500 RX FF 0A 00 0A", records[1].to_string());
+
+    let mut reader = CaptureReader::new(Cursor::new("0 TX FF\n5 XX FF\n"));
+    assert!(reader.next().unwrap().is_ok());
+    let error = reader.next().unwrap().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Parse));
+    assert!(error.to_string().contains("line 2"));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_capture_replay() {
+    let clock = ManualClock::new();
+    let text = record_session(&clock);
+    let records = CaptureReader::new(Cursor::new(text))
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+    let replay_clock = ManualClock::new();
+    let (mut port, device) = timed_port(&replay_clock);
+    replay_capture(records.clone(), &mut port, &replay_clock, 1.0).unwrap();
+    assert_eq!(
+        vec![
+            (secs(0), Message::go_to_preset(10, 5).unwrap()),
+            (Duration::from_micros(2_012_500), stop(10)),
+        ],
+        received(&device)
+    );
+
+    let replay_clock = ManualClock::new();
+    let (mut port, device) = timed_port(&replay_clock);
+    replay_capture(records.clone(), &mut port, &replay_clock, 4.0).unwrap();
+    let times: Vec<Duration> = received(&device).iter().map(|(t, _)| *t).collect();
+    assert_eq!(
+        vec![Duration::from_secs(0), Duration::from_micros(503_125)],
+        times
+    );
+
+    assert!(replay_capture(records, &mut port, &replay_clock, 0.0).is_err());
+}
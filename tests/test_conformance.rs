@@ -0,0 +1,167 @@
+use std::collections::BTreeSet;
+
+use pelcodrs::conformance::{self, Vector};
+use pelcodrs::*;
+
+/// Message built by the constructors of the crate for a vector.
+fn build(vector: &Vector) -> Result<Message> {
+    let standard = |f: fn(&mut MessageBuilder) -> &mut MessageBuilder| {
+        let mut builder = MessageBuilder::new(10).unwrap();
+        f(&mut builder);
+        builder.finalize()
+    };
+    let adjust = |delta: bool, new: u16, change: i16| {
+        if delta {
+            AdjustmentValue::Delta(change)
+        } else {
+            AdjustmentValue::New(new)
+        }
+    };
+
+    match vector.name {
+        "stop" => standard(|b| b.stop()),
+        "pan_left" => standard(|b| b.left().pan(Speed::Range(0.5))),
+        "pan_right_turbo" => standard(|b| b.right().pan(Speed::Turbo)),
+        "tilt_up" => standard(|b| b.up().tilt(Speed::Range(1.0))),
+        "tilt_down" => standard(|b| b.down().tilt(Speed::Range(0.25))),
+        "zoom_tele" => standard(|b| b.zoom_in()),
+        "zoom_wide" => standard(|b| b.zoom_out()),
+        "focus_far" => standard(|b| b.focus_far()),
+        "focus_near" => standard(|b| b.focus_near()),
+        "iris_open" => standard(|b| b.open_iris()),
+        "iris_close" => standard(|b| b.close_iris()),
+        "camera_on" => standard(|b| b.camera_on()),
+        "camera_off" => standard(|b| b.camera_off()),
+        "auto_scan" => standard(|b| b.auto_scan()),
+        "manual_scan" => standard(|b| b.manual_scan()),
+        "set_preset" => Message::set_preset(10, 5),
+        "clear_preset" => Message::clear_preset(10, 5),
+        "go_to_preset" => Message::go_to_preset(10, 5),
+        "go_to_preset_broadcast" => Message::go_to_preset(Address::Broadcast, 1),
+        "go_to_preset_checksum_wrap" => Message::go_to_preset(254, 255),
+        "flip_180" => Message::flip_180(10),
+        "go_to_zero_pan" => Message::go_to_zero_pan(10),
+        "set_auxiliary" => Message::set_auxiliary(10, 0, 2),
+        "clear_auxiliary" => Message::clear_auxiliary(10, 0, 2),
+        "remote_reset" => Message::remote_reset(10),
+        "set_zone_start" => Message::set_zone_start(10, 3),
+        "set_zone_end" => Message::set_zone_end(10, 3),
+        "zone_scan_on" => Message::zone_scan_on(10),
+        "zone_scan_off" => Message::zone_scan_off(10),
+        "write_char_to_screen" => Message::write_char_to_screen(10, 4, 'A'),
+        "clear_screen" => Message::clear_screen(10),
+        "alarm_acknowledge" => Message::alarm_acknowledge(10, 7),
+        "set_pattern_start" => Message::set_pattern_start(10, 1),
+        "set_pattern_stop" => Message::set_pattern_stop(10, 1),
+        "run_pattern" => Message::run_pattern(10, 1),
+        "set_zoom_speed" => Message::set_zoom_speed(10, ZoomSpeed::High),
+        "set_focus_speed" => Message::set_focus_speed(10, FocusSpeed::Medium),
+        "reset_camera_to_defaults" => Message::reset_camera_to_defaults(10),
+        "auto_focus" => Message::auto_focus(10, AutoCtrl::Auto),
+        "auto_iris" => Message::auto_iris(10, AutoCtrl::Off),
+        "agc" => Message::agc(10, AutoCtrl::Auto),
+        "backlight_compensation_on" => Message::backlight_compensation(10, OnOff::On),
+        "backlight_compensation_off" => Message::backlight_compensation(10, OnOff::Off),
+        "auto_white_balance_on" => Message::auto_white_balance(10, OnOff::On),
+        "auto_white_balance_off" => Message::auto_white_balance(10, OnOff::Off),
+        "enable_device_phase_delay_mode" => Message::enable_device_phase_delay_mode(10),
+        "shutter_bytes" => Message::set_shutter_speed(10, ShutterSpeed::Bytes(0x01, 0x02)),
+        "shutter_default" => Message::set_shutter_speed(10, ShutterSpeed::DefaultValue),
+        "shutter_increment" => Message::set_shutter_speed(10, ShutterSpeed::Increment),
+        "shutter_decrement" => Message::set_shutter_speed(10, ShutterSpeed::Decrement),
+        "shutter_pal" => Message::set_shutter_speed(10, ShutterSpeed::PAL),
+        "shutter_ntsc" => Message::set_shutter_speed(10, ShutterSpeed::NTSC),
+        "shutter_value" => Message::set_shutter_speed(10, ShutterSpeed::Value(1000)),
+        "shutter_auto" => Message::set_shutter_speed(10, ShutterSpeed::AutoShutter),
+        "shutter_index" => Message::set_shutter_speed(10, ShutterSpeed::Index(12)),
+        "line_lock_phase_new" | "line_lock_phase_delta" => Message::adjust_line_lock_phase_delay(
+            10,
+            adjust(vector.name.ends_with("delta"), 300, -5),
+        ),
+        "white_balance_rb_new" | "white_balance_rb_delta" => {
+            Message::adjust_white_balance_rb(10, adjust(vector.name.ends_with("delta"), 128, 10))
+        }
+        "white_balance_mg_new" | "white_balance_mg_delta" => {
+            Message::adjust_white_balance_mg(10, adjust(vector.name.ends_with("delta"), 128, -10))
+        }
+        "gain_new" | "gain_delta" => {
+            Message::adjust_gain(10, adjust(vector.name.ends_with("delta"), 512, 1))
+        }
+        "auto_iris_level_new" | "auto_iris_level_delta" => {
+            Message::adjust_auto_iris_level(10, adjust(vector.name.ends_with("delta"), 64, -1))
+        }
+        "auto_iris_peak_new" | "auto_iris_peak_delta" => {
+            Message::adjust_auto_iris_peak(10, adjust(vector.name.ends_with("delta"), 64, 2))
+        }
+        "query" => Message::query(),
+        "set_pan_position" => Message::set_pan_position(10, 9000),
+        name => panic!("No constructor for the vector {}", name),
+    }
+}
+
+#[test]
+fn test_conformance_constructors() {
+    for vector in conformance::vectors() {
+        let message = build(&vector).unwrap();
+        if let Err(e) = vector.check(message.as_ref()) {
+            panic!("{}", e);
+        }
+    }
+}
+
+#[test]
+fn test_conformance_text() {
+    for vector in conformance::vectors() {
+        let message: Message = vector.command.parse().unwrap();
+        assert_eq!(vector.message(), message, "{}", vector.name);
+        assert_eq!(vector.command, message.to_string(), "{}", vector.name);
+    }
+}
+
+#[test]
+fn test_conformance_coverage() {
+    let vectors = conformance::vectors();
+    let names: BTreeSet<&str> = vectors.iter().map(|v| v.name).collect();
+    assert_eq!(vectors.len(), names.len(), "Duplicate vector names");
+
+    // Every extended opcode
+    let opcodes: BTreeSet<u8> = vectors
+        .iter()
+        .map(|v| v.frame[3])
+        .filter(|opcode| opcode & 1 == 1)
+        .collect();
+    let expected: BTreeSet<u8> = (0x03..=0x45)
+        .step_by(2)
+        .filter(|opcode| *opcode != 0x0D)
+        .chain(std::iter::once(0x4B))
+        .collect();
+    assert_eq!(expected, opcodes);
+
+    // Every bit of the standard commands
+    let bits = vectors
+        .iter()
+        .filter(|v| v.frame[3] & 1 == 0)
+        .fold([0u8; 2], |acc, v| {
+            [acc[0] | v.frame[2], acc[1] | v.frame[3]]
+        });
+    assert_eq!([0x9F, 0xFE], bits);
+}
+
+#[test]
+fn test_conformance_check() {
+    let vector = conformance::vector("go_to_preset").unwrap();
+    assert_eq!("addr=10 GOTO_PRESET 5", vector.command);
+    assert!(vector
+        .check(&[0xFF, 0x0A, 0x00, 0x07, 0x00, 0x05, 0x16])
+        .is_ok());
+
+    let error = vector
+        .check(&[0xFF, 0x0A, 0x00, 0x07, 0x00, 0x05, 0x17])
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidValue));
+    assert_eq!(
+        "go_to_preset: expected FF 0A 00 07 00 05 16, got FF 0A 00 07 00 05 17",
+        error.to_string()
+    );
+    assert!(conformance::vector("unknown").is_none());
+}
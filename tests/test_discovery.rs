@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use pelcodrs::*;
+
+/// Bus of simulated devices answering the queries after some latency.
+struct Bus {
+    clock: ManualClock,
+    devices: BTreeMap<u8, (&'static str, Duration)>,
+    corrupted: Vec<u8>,
+    cancel_at: Option<(u8, Arc<AtomicBool>)>,
+    queried: Vec<(Duration, u8)>,
+    output: Vec<u8>,
+}
+
+impl Bus {
+    fn new(clock: &ManualClock) -> Bus {
+        Bus {
+            clock: clock.clone(),
+            devices: BTreeMap::new(),
+            corrupted: Vec::new(),
+            cancel_at: None,
+            queried: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+}
+
+impl Read for Bus {
+    fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        let count = self.output.len().min(buf.len());
+        buf[..count].copy_from_slice(&self.output[..count]);
+        self.output.drain(..count);
+        Ok(count)
+    }
+}
+
+impl Write for Bus {
+    fn write(&mut self, buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
+        let address = buf[1];
+        assert_eq!(0x45, buf[3]);
+        self.queried.push((self.clock.now(), address));
+
+        if let Some((part_number, latency)) = self.devices.get(&address) {
+            self.clock.advance(*latency);
+            let mut response = vec![0xFF, address];
+            response.extend_from_slice(format!("{:15}", part_number).as_bytes());
+            response.push(checksum(&response[1..]));
+            if self.corrupted.contains(&address) {
+                response[17] ^= 0xFF;
+            }
+            self.output.extend_from_slice(&response);
+        }
+        if let Some((cancel_address, flag)) = self.cancel_at.as_ref() {
+            if *cancel_address == address {
+                flag.store(true, Ordering::SeqCst);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_discovery_scan() {
+    let clock = ManualClock::new();
+    let mut bus = Bus::new(&clock);
+    bus.devices
+        .insert(2, ("DD5-PTZ", Duration::from_millis(20)));
+    bus.devices.insert(4, ("SPECTRA", Duration::from_millis(5)));
+    bus.devices.insert(9, ("OUTSIDE", Duration::from_millis(5)));
+    bus.devices.insert(3, ("NOISY", Duration::from_millis(5)));
+    bus.corrupted.push(3);
+
+    let mut port = PelcoDPort::new(bus);
+    port.set_clock(clock.clone());
+    let inventory = Discovery::new()
+        .set_addresses(0..=5)
+        .set_timeout(Duration::from_millis(100))
+        .set_interval(Duration::from_millis(50))
+        .run(&mut port)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            (2, Some("DD5-PTZ".to_string()), Duration::from_millis(20)),
+            (4, Some("SPECTRA".to_string()), Duration::from_millis(5)),
+        ],
+        inventory
+            .devices
+            .iter()
+            .map(|d| (d.address, d.part_number(), d.latency))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(None, inventory.devices[0].baud);
+    assert_eq!(vec![3], inventory.invalid);
+    assert_eq!(5, inventory.probed);
+    assert!(!inventory.cancelled);
+
+    // Queries spaced by the interval from the end of the previous write, or
+    // by the timeout
+    let times: Vec<(u128, u8)> = port
+        .get_ref()
+        .queried
+        .iter()
+        .map(|(t, a)| (t.as_millis(), *a))
+        .collect();
+    assert_eq!(vec![(0, 1), (100, 2), (170, 3), (225, 4), (280, 5)], times);
+    assert_eq!(Duration::from_millis(380), inventory.duration);
+
+    // The rate limit of the port is restored
+    assert_eq!(RateLimit::default(), port.rate_limit());
+}
+
+#[test]
+fn test_discovery_cancel() {
+    let clock = ManualClock::new();
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut bus = Bus::new(&clock);
+    bus.devices.insert(1, ("DD5-PTZ", Duration::from_millis(1)));
+    bus.cancel_at = Some((3, flag.clone()));
+
+    let mut port = PelcoDPort::new(bus);
+    port.set_clock(clock);
+    let inventory = Discovery::new()
+        .set_timeout(Duration::from_millis(10))
+        .set_cancel_flag(flag)
+        .run(&mut port)
+        .unwrap();
+
+    assert!(inventory.cancelled);
+    assert_eq!(3, inventory.probed);
+    assert_eq!(1, inventory.devices.len());
+}
+
+#[test]
+fn test_discovery_default_addresses() {
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(Bus::new(&clock));
+    port.set_clock(clock);
+    let inventory = Discovery::new()
+        .set_timeout(Duration::from_millis(1))
+        .run(&mut port)
+        .unwrap();
+
+    assert_eq!(254, inventory.probed);
+    let queried = &port.get_ref().queried;
+    assert_eq!(Some(254), queried.last().map(|(_, a)| *a));
+}
@@ -0,0 +1,192 @@
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pelcodrs::*;
+
+type Log = Arc<Mutex<Vec<(u128, String)>>>;
+
+/// Transport echoing the written bytes, followed by the device reply, and
+/// logging the writes and the RTS changes with their time in milliseconds.
+struct EchoTransport {
+    clock: ManualClock,
+    log: Log,
+    reply: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl EchoTransport {
+    fn new(clock: &ManualClock, log: &Log, reply: &[u8]) -> EchoTransport {
+        EchoTransport {
+            clock: clock.clone(),
+            log: log.clone(),
+            reply: reply.to_vec(),
+            output: Vec::new(),
+        }
+    }
+
+    fn log(&self, entry: &str) {
+        self.log
+            .lock()
+            .unwrap()
+            .push((self.clock.now().as_millis(), entry.to_string()));
+    }
+}
+
+impl Read for EchoTransport {
+    fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        let count = self.output.len().min(buf.len());
+        buf[..count].copy_from_slice(&self.output[..count]);
+        self.output.drain(..count);
+        Ok(count)
+    }
+}
+
+impl Write for EchoTransport {
+    fn write(&mut self, buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
+        self.log("write");
+        self.output.extend_from_slice(buf);
+        self.output.extend_from_slice(&self.reply);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl RequestToSend for EchoTransport {
+    fn set_request_to_send(&mut self, level: bool) -> std::io::Result<()> {
+        self.log(if level { "rts high" } else { "rts low" });
+        Ok(())
+    }
+}
+
+fn entries(log: &Log) -> Vec<(u128, String)> {
+    log.lock().unwrap().drain(..).collect()
+}
+
+#[test]
+fn test_half_duplex_rts() {
+    let clock = ManualClock::new();
+    let log = Log::default();
+    let transport = EchoTransport::new(&clock, &log, &[0xFF, 0x0A, 0x00, 0x0A]);
+    let mut port = PelcoDPort::new(transport);
+    port.set_clock(clock.clone()).set_half_duplex(
+        RtsControl { inverted: true },
+        HalfDuplex {
+            tx_delay: Duration::from_millis(1),
+            rx_delay: Duration::from_millis(30),
+            discard_echo: true,
+        },
+    );
+    assert!(port.is_half_duplex());
+
+    let response = port
+        .transaction(Message::go_to_preset(10, 5).unwrap(), ResponseKind::General)
+        .unwrap();
+    assert_eq!(
+        Response::General {
+            address: 10,
+            alarms: 0
+        },
+        response
+    );
+    assert_eq!(
+        vec![
+            (0, "rts low".to_string()),
+            (1, "write".to_string()),
+            (31, "rts high".to_string()),
+        ],
+        entries(&log)
+    );
+
+    port.set_full_duplex();
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    assert_eq!(vec![(31, "write".to_string())], entries(&log));
+}
+
+#[test]
+fn test_half_duplex_echo() {
+    let clock = ManualClock::new();
+    let log = Log::default();
+    let direction = Arc::new(Mutex::new(Vec::new()));
+    let control = {
+        let direction = direction.clone();
+        CallbackControl::new(move |transmit| {
+            direction.lock().unwrap().push(transmit);
+            Ok(())
+        })
+    };
+
+    // Without discarding, the echo is parsed as a response
+    let transport = EchoTransport::new(&clock, &log, &[]);
+    let mut port = PelcoDPort::new(transport);
+    port.set_clock(clock.clone())
+        .set_half_duplex(control, HalfDuplex::default());
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    assert!(port
+        .receive_response(ResponseKind::Extended)
+        .unwrap()
+        .is_some());
+    assert_eq!(vec![true, false], *direction.lock().unwrap());
+
+    // With discarding, the bytes following the echo are kept
+    let transport = EchoTransport::new(&clock, &log, &[0xFF, 0x0A, 0x00, 0x0A]);
+    let mut port = PelcoDPort::new(transport);
+    port.set_half_duplex(
+        CallbackControl::new(|_| Ok(())),
+        HalfDuplex {
+            discard_echo: true,
+            ..HalfDuplex::default()
+        },
+    );
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    let mut buf = [0u8; 16];
+    let count = port.read(&mut buf).unwrap();
+    assert_eq!(&[0xFF, 0x0A, 0x00, 0x0A], &buf[..count]);
+}
+
+#[test]
+fn test_half_duplex_direction_error() {
+    let clock = ManualClock::new();
+    let log = Log::default();
+    let failures = Arc::new(Mutex::new(1));
+    let control = {
+        let failures = failures.clone();
+        CallbackControl::new(move |transmit| {
+            let mut failures = failures.lock().unwrap();
+            if !transmit && *failures > 0 {
+                *failures -= 1;
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "RTS failure",
+                ));
+            }
+            Ok(())
+        })
+    };
+    let transport = EchoTransport::new(&clock, &log, &[]);
+    let mut port = PelcoDPort::new(transport);
+    port.set_clock(clock.clone())
+        .set_half_duplex(control, HalfDuplex::default());
+
+    // The frame is written despite the failure, which is reported next
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    assert_eq!(1, port.metrics().frames_sent);
+    let error = port
+        .send_message(Message::go_to_preset(10, 6).unwrap())
+        .unwrap_err();
+    match error.kind() {
+        ErrorKind::Io(e) => assert_eq!("RTS failure", e.to_string()),
+        kind => panic!("Unexpected error kind {:?}", kind),
+    }
+    port.send_message(Message::go_to_preset(10, 7).unwrap())
+        .unwrap();
+    assert_eq!(2, port.metrics().frames_sent);
+    assert_eq!(2, entries(&log).len());
+}
@@ -0,0 +1,62 @@
+use pelcodrs::*;
+
+const CAMERA: Address = match Address::unit(10) {
+    Some(address) => address,
+    None => panic!("invalid address"),
+};
+
+static TABLE: [Message; 4] = [
+    frames::stop(CAMERA),
+    frames::flip_180(CAMERA),
+    frames::set_shutter_speed(CAMERA, ShutterSpeed::Value(0x1234)),
+    frames::adjust_gain(Address::Broadcast, AdjustmentValue::Delta(-2)),
+];
+
+const CHECKSUM: u8 = checksum(&[0x0A, 0x88, 0x90, 0x00, 0x40]);
+const FRAME: Message = Message::from_bytes(10, [0x00, 0x07, 0x00, 0x05]);
+
+#[test]
+fn test_const_construction() {
+    assert_eq!(0x62, CHECKSUM);
+    assert_eq!(Message::go_to_preset(10, 5).unwrap(), FRAME);
+    assert_eq!(10, FRAME.address());
+    assert_eq!(None, Address::unit(0));
+
+    assert_eq!(
+        MessageBuilder::new(10).unwrap().stop().finalize().unwrap(),
+        TABLE[0]
+    );
+    assert_eq!(Message::flip_180(10).unwrap(), TABLE[1]);
+    assert_eq!(
+        &[0xFF, 0x0A, 0x00, 0x37, 0x12, 0x34, 0x87],
+        TABLE[2].as_ref()
+    );
+    assert_eq!(
+        &[0xFF, 0x00, 0x01, 0x3F, 0xFF, 0xFE, 0x3D],
+        TABLE[3].as_ref()
+    );
+}
+
+#[test]
+fn test_frames_match_constructors() {
+    assert_eq!(
+        Message::remote_reset(10).unwrap(),
+        frames::remote_reset(CAMERA)
+    );
+    assert_eq!(
+        Message::clear_screen(10).unwrap(),
+        frames::clear_screen(CAMERA)
+    );
+    assert_eq!(
+        Message::set_auxiliary(10, 1, 2).unwrap(),
+        frames::set_auxiliary(CAMERA, 1, 2)
+    );
+    assert_eq!(
+        Message::backlight_compensation(10, OnOff::On).unwrap(),
+        frames::backlight_compensation(CAMERA, OnOff::On)
+    );
+    assert_eq!(Message::query().unwrap(), frames::query(Address::Broadcast));
+
+    // The constructors of Message still validate the address
+    assert!(Message::remote_reset(0).is_err());
+}
@@ -13,6 +13,7 @@ fn test_message_new() {
     assert_eq!(&[0xFF, 0x0A, 0x88, 0x90, 0x00, 0x20, 0x42], msg.as_ref());
 
     let msg2 = MessageBuilder::new(10)
+        .unwrap()
         .camera_on()
         .focus_far()
         .down()
@@ -40,23 +41,33 @@ fn test_new_message_with_try_from() {
 
 #[test]
 fn test_message_build() {
-    let msg = MessageBuilder::new(1).camera_on().finalize().unwrap();
+    let msg = MessageBuilder::new(1)
+        .unwrap()
+        .camera_on()
+        .finalize()
+        .unwrap();
     assert_eq!(&[0xFF, 0x01, 0x88, 0x00, 0x00, 0x00, 0x89], msg.as_ref());
 
-    let msg = MessageBuilder::new(1).camera_off().finalize().unwrap();
+    let msg = MessageBuilder::new(1)
+        .unwrap()
+        .camera_off()
+        .finalize()
+        .unwrap();
     assert_eq!(&[0xFF, 0x01, 0x08, 0x00, 0x00, 0x00, 0x09], msg.as_ref());
 
     let msg = MessageBuilder::new(2)
+        .unwrap()
         .left()
         .pan(Speed::Range(0.5))
         .finalize()
         .unwrap();
     assert_eq!(&[0xFF, 0x02, 0x00, 0x04, 0x20, 0x00, 0x26], msg.as_ref());
 
-    let msg = MessageBuilder::new(2).stop().finalize().unwrap();
+    let msg = MessageBuilder::new(2).unwrap().stop().finalize().unwrap();
     assert_eq!(&[0xFF, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02], msg.as_ref());
 
     let msg = MessageBuilder::new(10)
+        .unwrap()
         .camera_on()
         .focus_far()
         .direction(Direction::DOWN)
@@ -116,3 +127,153 @@ fn test_zoom_focus_speed() {
     let msg = Message::set_focus_speed(12, FocusSpeed::Highest).unwrap();
     assert_eq!(&[0xFF, 12, 0, 0x27, 0, 3, 54], msg.as_ref());
 }
+
+#[test]
+fn test_set_pan_position() {
+    let msg = Message::set_pan_position(1, 9000).unwrap();
+    assert_eq!(&[0xFF, 1, 0, 0x4B, 0x23, 0x28, 0x97], msg.as_ref());
+
+    let _ = Message::set_pan_position(1, 35999).expect("Position 35999 should be valid");
+    let _ = Message::set_pan_position(1, 36000).expect_err("Position 36000 should fail");
+}
+
+#[test]
+fn test_message_display() {
+    let msg = Message::go_to_preset(10, 5).unwrap();
+    assert_eq!("addr=10 GOTO_PRESET 5", msg.to_string());
+    assert_eq!("FF 0A 00 07 00 05 16", format!("{:X}", msg));
+
+    let msg = MessageBuilder::new(1)
+        .unwrap()
+        .camera_on()
+        .finalize()
+        .unwrap();
+    assert_eq!("addr=1 CAMERA ON", msg.to_string());
+    let msg = MessageBuilder::new(1).unwrap().stop().finalize().unwrap();
+    assert_eq!("addr=1 STOP", msg.to_string());
+
+    let msg = Message::adjust_gain(2, AdjustmentValue::Delta(-3)).unwrap();
+    assert_eq!("addr=2 GAIN DELTA -3", msg.to_string());
+    let msg = Message::write_char_to_screen(11, 32, 'F').unwrap();
+    assert_eq!("addr=11 WRITE_CHAR 32 0x46", msg.to_string());
+    let msg = Message::set_auxiliary(4, 2, 1).unwrap();
+    assert_eq!("addr=4 EXT 0x09 0x02 0x00 0x01", msg.to_string());
+
+    let msg = Message::from([1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!("RAW 01 02 03 04 05 06 07", msg.to_string());
+}
+
+#[test]
+fn test_message_from_str() {
+    let msg: Message = "addr=3 pan left 0x20 tilt up 16 zoom tele".parse().unwrap();
+    let expected = MessageBuilder::new(3)
+        .unwrap()
+        .left()
+        .pan(Speed::Range(0.5))
+        .up()
+        .tilt(Speed::Range(0.25))
+        .zoom_in()
+        .finalize()
+        .unwrap();
+    assert_eq!(expected, msg);
+
+    let msg: Message = "addr=0x0A CAMERA ON FOCUS FAR TILT DOWN 0x20"
+        .parse()
+        .unwrap();
+    assert_eq!(&[0xFF, 0x0A, 0x88, 0x90, 0x00, 0x20, 0x42], msg.as_ref());
+
+    let msg: Message = "RAW 01 02 03 04 05 06 07".parse().unwrap();
+    assert_eq!(Message::from([1, 2, 3, 4, 5, 6, 7]), msg);
+
+    let _ = "".parse::<Message>().expect_err("Empty string should fail");
+    let _ = "FF 0A 00 07 00 05"
+        .parse::<Message>()
+        .expect_err("Too few bytes");
+    let _ = "FF 0A 00 07 00 05 1G"
+        .parse::<Message>()
+        .expect_err("Invalid hex");
+    let _ = "0XFF 0X0X0A 00 07 00 05 16"
+        .parse::<Message>()
+        .expect_err("Repeated prefix");
+    assert_eq!(
+        Message::go_to_preset(10, 5).unwrap(),
+        "0xFF 0x0A 00 07 00 05 16".parse().unwrap()
+    );
+    let _ = "addr=300 STOP"
+        .parse::<Message>()
+        .expect_err("Invalid address");
+    let _ = "addr=1 JUMP"
+        .parse::<Message>()
+        .expect_err("Unknown command");
+    let _ = "addr=1 GOTO_PRESET"
+        .parse::<Message>()
+        .expect_err("Missing argument");
+    let err = "addr=1 GAIN MORE 3".parse::<Message>().unwrap_err();
+    match err.kind() {
+        ErrorKind::Parse => (),
+        kind => panic!("Unexpected error kind {:?}", kind),
+    }
+}
+
+#[test]
+fn test_message_text_round_trip() {
+    let messages = vec![
+        Message::set_preset(1, 2).unwrap(),
+        Message::clear_preset(1, 2).unwrap(),
+        Message::set_auxiliary(1, 0, 2).unwrap(),
+        Message::clear_auxiliary(1, 0, 2).unwrap(),
+        Message::remote_reset(1).unwrap(),
+        Message::set_zone_start(1, 2).unwrap(),
+        Message::set_zone_end(1, 2).unwrap(),
+        Message::clear_screen(1).unwrap(),
+        Message::alarm_acknowledge(1, 2).unwrap(),
+        Message::zone_scan_on(1).unwrap(),
+        Message::zone_scan_off(1).unwrap(),
+        Message::set_pattern_start(1, 2).unwrap(),
+        Message::set_pattern_stop(1, 2).unwrap(),
+        Message::run_pattern(1, 2).unwrap(),
+        Message::set_zoom_speed(1, ZoomSpeed::High).unwrap(),
+        Message::set_focus_speed(1, FocusSpeed::Slow).unwrap(),
+        Message::reset_camera_to_defaults(1).unwrap(),
+        Message::auto_focus(1, AutoCtrl::Off).unwrap(),
+        Message::auto_iris(1, AutoCtrl::Auto).unwrap(),
+        Message::agc(1, AutoCtrl::Off).unwrap(),
+        Message::backlight_compensation(1, OnOff::On).unwrap(),
+        Message::auto_white_balance(1, OnOff::Off).unwrap(),
+        Message::enable_device_phase_delay_mode(1).unwrap(),
+        Message::set_shutter_speed(1, ShutterSpeed::Value(1000)).unwrap(),
+        Message::adjust_line_lock_phase_delay(1, AdjustmentValue::New(500)).unwrap(),
+        Message::adjust_white_balance_rb(1, AdjustmentValue::Delta(-500)).unwrap(),
+        Message::adjust_white_balance_mg(1, AdjustmentValue::New(0)).unwrap(),
+        Message::adjust_auto_iris_level(1, AdjustmentValue::Delta(1)).unwrap(),
+        Message::adjust_auto_iris_peak(1, AdjustmentValue::New(65535)).unwrap(),
+        Message::query().unwrap(),
+        Message::set_pan_position(1, 18000).unwrap(),
+        Message::from_bytes(1, [0x00, 0x7F, 0x01, 0x02]),
+        MessageBuilder::new(255)
+            .unwrap()
+            .camera_off()
+            .manual_scan()
+            .open_iris()
+            .close_iris()
+            .focus_near()
+            .zoom_out()
+            .right()
+            .down()
+            .pan(Speed::Turbo)
+            .finalize()
+            .unwrap(),
+        MessageBuilder::new(7)
+            .unwrap()
+            .auto_scan()
+            .finalize()
+            .unwrap(),
+        Message::new(7, Command1::SENSE, Command2::empty(), 0x10, 0),
+        Message::from([0x12; 7]),
+    ];
+
+    for msg in messages {
+        assert_eq!(msg, msg.to_string().parse().unwrap(), "{}", msg);
+        assert_eq!(msg, format!("{:X}", msg).parse().unwrap(), "{:X}", msg);
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use pelcodrs::*;
+
+#[test]
+fn test_metrics_sent() {
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+    port.set_clock(clock.clone());
+
+    clock.advance(Duration::from_secs(1));
+    port.send_message(Message::go_to_preset(10, 5).unwrap())
+        .unwrap();
+    clock.advance(Duration::from_secs(1));
+    port.send_message(MessageBuilder::new(10).unwrap().stop().finalize().unwrap())
+        .unwrap();
+    port.send_message(Message::go_to_preset(2, 1).unwrap())
+        .unwrap();
+    port.send_message(
+        MessageBuilder::new(2)
+            .unwrap()
+            .zoom_in()
+            .finalize()
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(Duration::from_secs(2), port.clock().now());
+    let metrics = port.metrics();
+    assert_eq!(4, metrics.frames_sent);
+    assert_eq!(28, metrics.bytes_written);
+    assert_eq!(Duration::from_secs(2), metrics.timestamp);
+
+    let camera = &metrics.addresses[&10];
+    assert_eq!(2, camera.frames_sent);
+    assert_eq!(14, camera.bytes_written);
+    assert_eq!(Some(Duration::from_secs(2)), camera.last_sent);
+    assert_eq!(None, camera.last_seen);
+    assert_eq!(
+        vec![("GOTO_PRESET", 1), ("STOP", 1)],
+        camera
+            .commands
+            .iter()
+            .map(|(c, n)| (*c, *n))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Some(&1), metrics.addresses[&2].commands.get("STANDARD"));
+
+    port.reset_metrics();
+    let metrics = port.metrics();
+    assert_eq!(0, metrics.frames_sent);
+    assert!(metrics.addresses.is_empty());
+}
+
+#[test]
+fn test_metrics_received() {
+    let clock = ManualClock::new();
+    let bytes = vec![
+        0x00, 0xFF, 0x0A, 0x01, 0x0B, // noise and general response
+        0xFF, 0x0A, 0x01, 0x00, // wrong checksum
+    ];
+    let mut port = PelcoDPort::new(Cursor::new(bytes));
+    port.set_clock(clock.clone());
+
+    clock.advance(Duration::from_secs(3));
+    assert!(port
+        .receive_response(ResponseKind::General)
+        .unwrap()
+        .is_some());
+    assert!(port.receive_response(ResponseKind::General).is_err());
+
+    clock.advance(Duration::from_millis(1500));
+    let metrics = port.metrics();
+    assert_eq!(9, metrics.bytes_read);
+    assert_eq!(1, metrics.replies_received);
+    assert_eq!(1, metrics.checksum_failures);
+    assert_eq!(1, metrics.addresses[&10].replies_received);
+    assert_eq!(
+        Some(Duration::from_secs(3)),
+        metrics.addresses[&10].last_seen
+    );
+
+    let text = metrics.to_prometheus("line\"1");
+    assert!(text.contains("pelcod_port_checksum_failures_total{port=\"line\\\"1\"} 1\n"));
+    assert!(text.contains("pelcod_replies_received_total{port=\"line\\\"1\",address=\"10\"} 1\n"));
+    assert!(text.contains("pelcod_last_reply_age_seconds{port=\"line\\\"1\",address=\"10\"} 1.5\n"));
+    assert!(text.contains("# TYPE pelcod_last_reply_age_seconds gauge\n"));
+}
@@ -0,0 +1,205 @@
+use std::io::ErrorKind as IoErrorKind;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::Failure;
+use pelcodrs::*;
+
+fn left() -> Message {
+    MessageBuilder::new(5)
+        .unwrap()
+        .left()
+        .pan(Speed::Range(0.5))
+        .finalize()
+        .unwrap()
+}
+
+fn up() -> Message {
+    MessageBuilder::new(5)
+        .unwrap()
+        .up()
+        .tilt(Speed::Range(0.2))
+        .finalize()
+        .unwrap()
+}
+
+#[test]
+fn test_unsorted_pattern() {
+    let steps = vec![
+        PatternStep::new(secs(2), left()),
+        PatternStep::new(secs(1), up()),
+    ];
+    let _ = Pattern::new(5, 1, steps).expect_err("Unsorted steps should fail");
+}
+
+#[test]
+fn test_pattern_other_address() {
+    let steps = vec![
+        PatternStep::new(secs(0), left()),
+        PatternStep::new(secs(1), Message::go_to_preset(6, 1).unwrap()),
+    ];
+    let _ = Pattern::new(5, 1, steps).expect_err("Steps for another address should fail");
+}
+
+#[test]
+fn test_record_pattern() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    let mut recorder = PatternRecorder::new();
+
+    let pattern = Pattern::new(
+        5,
+        2,
+        vec![
+            PatternStep::new(secs(0), left()),
+            PatternStep::new(secs(2), up()),
+        ],
+    )
+    .unwrap();
+    recorder.record(&mut port, pattern.clone()).unwrap();
+
+    assert_eq!(
+        vec![
+            (secs(0), Message::set_pattern_start(5, 2).unwrap()),
+            (secs(0), left()),
+            (secs(2), up()),
+            (secs(2), stop(5)),
+            (secs(2), Message::set_pattern_stop(5, 2).unwrap()),
+        ],
+        received(&device)
+    );
+    assert_eq!(Some(&pattern), recorder.pattern(5, 2));
+}
+
+#[test]
+fn test_record_live_and_replay() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    let mut recorder = PatternRecorder::new();
+
+    clock.advance(secs(100));
+    recorder.start(&mut port, 5, 1).unwrap();
+    let _ = recorder
+        .start(&mut port, 5, 2)
+        .expect_err("Recording should be already in progress");
+    recorder.send(&mut port, left()).unwrap();
+    clock.advance(secs(1));
+    recorder
+        .send(&mut port, Message::go_to_preset(6, 1).unwrap())
+        .unwrap();
+    clock.advance(secs(2));
+    recorder.send(&mut port, up()).unwrap();
+    clock.advance(secs(1));
+    let pattern = recorder.stop(&mut port).unwrap().clone();
+    assert!(!recorder.is_recording());
+
+    assert_eq!(
+        vec![
+            PatternStep::new(secs(0), left()),
+            PatternStep::new(secs(3), up()),
+            PatternStep::new(secs(4), stop(5)),
+        ],
+        pattern.steps()
+    );
+    assert_eq!(
+        Message::set_pattern_stop(5, 1).unwrap(),
+        received(&device).last().unwrap().1
+    );
+
+    recorder.run(&mut port, 5, 1, Playback::Local).unwrap();
+    assert_eq!(
+        vec![(secs(104), left()), (secs(107), up()), (secs(108), stop(5)),],
+        received(&device)
+    );
+
+    recorder.run(&mut port, 5, 1, Playback::Device).unwrap();
+    assert_eq!(Message::run_pattern(5, 1).unwrap(), received(&device)[0].1);
+
+    let _ = recorder
+        .run(&mut port, 5, 3, Playback::Local)
+        .expect_err("Pattern 3 is not stored");
+}
+
+#[test]
+fn test_record_pattern_error() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    let mut recorder = PatternRecorder::new();
+
+    // The first two writes go through, the third one, for the second step,
+    // fails
+    device
+        .inject(Failure::ShortWrite(7))
+        .inject(Failure::ShortWrite(7))
+        .inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    let pattern = Pattern::new(
+        5,
+        2,
+        vec![
+            PatternStep::new(secs(0), left()),
+            PatternStep::new(secs(2), up()),
+            PatternStep::new(secs(3), stop(5)),
+        ],
+    )
+    .unwrap();
+    let _ = recorder
+        .record(&mut port, pattern)
+        .expect_err("Recording should fail");
+
+    assert_eq!(
+        vec![
+            Message::set_pattern_start(5, 2).unwrap(),
+            left(),
+            stop(5),
+            Message::set_pattern_stop(5, 2).unwrap(),
+        ],
+        messages(&device)
+    );
+    assert!(recorder.pattern(5, 2).is_none());
+}
+
+#[test]
+fn test_replay_error() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    let pattern = Pattern::new(
+        5,
+        1,
+        vec![
+            PatternStep::new(secs(0), left()),
+            PatternStep::new(secs(1), up()),
+        ],
+    )
+    .unwrap();
+
+    // The second step fails
+    device
+        .inject(Failure::ShortWrite(7))
+        .inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    let _ = pattern.replay(&mut port).expect_err("Replay should fail");
+    assert_eq!(vec![left(), stop(5)], messages(&device));
+}
+
+#[test]
+fn test_stop_live_recording_error() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    let mut recorder = PatternRecorder::new();
+
+    recorder.start(&mut port, 5, 1).unwrap();
+    recorder.send(&mut port, left()).unwrap();
+    clock.advance(secs(2));
+    device.inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    let _ = recorder.stop(&mut port).expect_err("Stop should fail");
+    assert!(recorder.is_recording());
+
+    let pattern = recorder.stop(&mut port).unwrap();
+    assert_eq!(
+        &[
+            PatternStep::new(secs(0), left()),
+            PatternStep::new(secs(2), stop(5)),
+        ],
+        pattern.steps()
+    );
+}
@@ -0,0 +1,53 @@
+use pelcodrs::*;
+
+#[test]
+fn test_classify_preset() {
+    let table = PresetTable::SPECTRA;
+    assert_eq!(Preset::User(1), table.classify(1));
+    assert_eq!(Preset::Special(SpecialPreset::Flip180), table.classify(33));
+    assert_eq!(Preset::Special(SpecialPreset::Menu), table.classify(95));
+    assert_eq!(Some(94), table.preset_id(SpecialPreset::RemoteReset));
+    assert_eq!(None, PresetTable::GENERIC.preset_id(SpecialPreset::Menu));
+}
+
+#[test]
+fn test_set_user_preset() {
+    let msg = Message::set_user_preset(1, 1, &PresetTable::SPECTRA).unwrap();
+    assert_eq!(Message::set_preset(1, 1).unwrap(), msg);
+
+    let err = Message::set_user_preset(1, 33, &PresetTable::GENERIC)
+        .expect_err("Preset 33 should be reserved");
+    match err.kind() {
+        ErrorKind::ReservedPreset(SpecialPreset::Flip180) => (),
+        kind => panic!("Unexpected error kind {:?}", kind),
+    }
+
+    let _ = Message::set_user_preset(1, 99, &PresetTable::SPECTRA)
+        .expect_err("Preset 99 should be reserved");
+    let _ = Message::set_user_preset(1, 99, &PresetTable::GENERIC)
+        .expect("Preset 99 should be available");
+    let _ =
+        Message::set_user_preset(1, 0, &PresetTable::GENERIC).expect_err("Preset 0 should fail");
+}
+
+#[test]
+fn test_call_special_preset() {
+    let table = PresetTable::GENERIC;
+    assert_eq!(
+        Message::flip_180(10).unwrap(),
+        Message::call_special_preset(10, SpecialPreset::Flip180, &table).unwrap()
+    );
+    assert_eq!(
+        Message::go_to_zero_pan(10).unwrap(),
+        Message::call_special_preset(10, SpecialPreset::GoToZeroPan, &table).unwrap()
+    );
+    let _ = Message::call_special_preset(10, SpecialPreset::AutoScan, &table)
+        .expect_err("Auto scan is not in the generic table");
+}
+
+#[test]
+fn test_const_preset_id() {
+    const FLIP: Option<u8> = PresetTable::SPECTRA.preset_id(SpecialPreset::Flip180);
+    assert_eq!(Some(33), FLIP);
+    assert_eq!(None, PresetTable::GENERIC.preset_id(SpecialPreset::Menu));
+}
@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+use std::io::Cursor;
+
+use pelcodrs::*;
+use proptest::prelude::*;
+
+/// Messages of all the constructors, for valid arguments.
+fn all_messages(address: u8, value: u8, word: u16, delta: i16, character: char) -> Vec<Message> {
+    let adjust = [AdjustmentValue::New(word), AdjustmentValue::Delta(delta)];
+    let mut messages = vec![
+        Message::set_preset(address, value).unwrap(),
+        Message::clear_preset(address, value).unwrap(),
+        Message::go_to_preset(address, value).unwrap(),
+        Message::flip_180(address).unwrap(),
+        Message::go_to_zero_pan(address).unwrap(),
+        Message::set_auxiliary(address, value, value).unwrap(),
+        Message::clear_auxiliary(address, value, value).unwrap(),
+        Message::remote_reset(address).unwrap(),
+        Message::set_zone_start(address, value).unwrap(),
+        Message::set_zone_end(address, value).unwrap(),
+        Message::write_char_to_screen(address, value, character).unwrap(),
+        Message::clear_screen(address).unwrap(),
+        Message::alarm_acknowledge(address, value).unwrap(),
+        Message::zone_scan_on(address).unwrap(),
+        Message::zone_scan_off(address).unwrap(),
+        Message::set_pattern_start(address, value).unwrap(),
+        Message::set_pattern_stop(address, value).unwrap(),
+        Message::run_pattern(address, value).unwrap(),
+        Message::set_zoom_speed(address, ZoomSpeed::High).unwrap(),
+        Message::set_focus_speed(address, FocusSpeed::Slow).unwrap(),
+        Message::reset_camera_to_defaults(address).unwrap(),
+        Message::auto_focus(address, AutoCtrl::Auto).unwrap(),
+        Message::auto_iris(address, AutoCtrl::Off).unwrap(),
+        Message::agc(address, AutoCtrl::Auto).unwrap(),
+        Message::backlight_compensation(address, OnOff::Value(value)).unwrap(),
+        Message::auto_white_balance(address, OnOff::On).unwrap(),
+        Message::enable_device_phase_delay_mode(address).unwrap(),
+        Message::set_shutter_speed(address, ShutterSpeed::Value(word)).unwrap(),
+        Message::set_pan_position(address, word % 36000).unwrap(),
+    ];
+    for ctrl in adjust.iter() {
+        messages.push(Message::adjust_line_lock_phase_delay(address, *ctrl).unwrap());
+        messages.push(Message::adjust_white_balance_rb(address, *ctrl).unwrap());
+        messages.push(Message::adjust_white_balance_mg(address, *ctrl).unwrap());
+        messages.push(Message::adjust_gain(address, *ctrl).unwrap());
+        messages.push(Message::adjust_auto_iris_level(address, *ctrl).unwrap());
+        messages.push(Message::adjust_auto_iris_peak(address, *ctrl).unwrap());
+    }
+    messages
+}
+
+fn standard_message(address: u8, bits: u8, pan: f32, tilt: f32) -> Message {
+    let mut builder = MessageBuilder::new(address).unwrap();
+    builder
+        .direction(Direction::from_bits_truncate(bits))
+        .pan(Speed::Range(pan))
+        .tilt(Speed::Range(tilt));
+    if bits & 0x01 != 0 {
+        builder.zoom_in();
+    }
+    if bits & 0x20 != 0 {
+        builder.camera_on();
+    }
+    if bits & 0x40 != 0 {
+        builder.focus_near();
+    }
+    if bits & 0x80 != 0 {
+        builder.open_iris();
+    }
+    builder.finalize().unwrap()
+}
+
+fn round_trip(message: Message) {
+    let text = message.to_string();
+    assert_eq!(message, text.parse::<Message>().unwrap(), "{}", text);
+    let hex = format!("{:X}", message);
+    assert_eq!(message, hex.parse::<Message>().unwrap(), "{}", hex);
+}
+
+proptest! {
+    #[test]
+    fn test_constructors_frame(
+        address in 1u8..,
+        value in 1u8..,
+        word: u16,
+        delta: i16,
+        character in proptest::char::range(' ', '~'),
+    ) {
+        for message in all_messages(address, value, word, delta, character) {
+            let bytes = message.as_ref();
+            prop_assert_eq!(0xFF, bytes[0]);
+            prop_assert_eq!(address, bytes[1]);
+            prop_assert_eq!(checksum(&bytes[1..6]), bytes[6]);
+        }
+    }
+
+    #[test]
+    fn test_constructors_round_trip(
+        address in 1u8..,
+        value in 1u8..,
+        word: u16,
+        delta: i16,
+        character in proptest::char::range(' ', '~'),
+    ) {
+        for message in all_messages(address, value, word, delta, character) {
+            round_trip(message);
+            let bytes = message.as_ref();
+            prop_assert_eq!(message, Message::try_from(bytes).unwrap());
+            let words = [bytes[2], bytes[3], bytes[4], bytes[5]];
+            prop_assert_eq!(message, Message::from_bytes(address, words));
+        }
+    }
+
+    #[test]
+    fn test_standard_round_trip(
+        address in 1u8..,
+        bits: u8,
+        pan in 0.0f32..=1.0,
+        tilt in 0.0f32..=1.0,
+    ) {
+        round_trip(standard_message(address, bits, pan, tilt));
+    }
+
+    #[test]
+    fn test_raw_round_trip(bytes: [u8; 7]) {
+        round_trip(Message::from(bytes));
+    }
+
+    #[test]
+    fn test_response_parser_no_panic(
+        chunks in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..32), 0..16),
+    ) {
+        for kind in [ResponseKind::General, ResponseKind::Extended, ResponseKind::Query].iter() {
+            let mut parser = ResponseParser::new(*kind);
+            let mut total = 0;
+            for chunk in chunks.iter() {
+                parser.push(chunk);
+                total += chunk.len();
+                while let Some(result) = parser.next_response() {
+                    if let Ok(response) = result {
+                        prop_assert_eq!(*kind, response.kind());
+                    }
+                }
+                prop_assert!(parser.pending() <= total);
+            }
+        }
+    }
+
+    #[test]
+    fn test_text_parsers_no_panic(text in "\\PC*") {
+        let _ = text.parse::<Message>();
+        let _ = text.parse::<Script>();
+        for record in CaptureReader::new(Cursor::new(text.as_bytes())) {
+            let _ = record;
+        }
+    }
+
+    #[test]
+    fn test_response_parse_no_panic(bytes in proptest::collection::vec(any::<u8>(), 0..24)) {
+        for kind in [ResponseKind::General, ResponseKind::Extended, ResponseKind::Query].iter() {
+            let _ = Response::parse(*kind, &bytes);
+        }
+    }
+}
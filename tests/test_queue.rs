@@ -0,0 +1,159 @@
+use std::io::ErrorKind as IoErrorKind;
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::{Failure, MockTransport};
+use pelcodrs::*;
+
+#[test]
+fn test_queue_priority() {
+    assert_eq!(Priority::High, Priority::of(&stop(1)));
+    assert_eq!(
+        Priority::Low,
+        Priority::of(&Message::clear_screen(1).unwrap())
+    );
+    assert_eq!(
+        Priority::Normal,
+        Priority::of(&Message::go_to_preset(1, 1).unwrap())
+    );
+
+    let mut queue = SendQueue::new();
+    queue.push(Message::write_char_to_screen(1, 0, 'A').unwrap());
+    queue.push(Message::go_to_preset(1, 1).unwrap());
+    queue.push(Message::write_char_to_screen(1, 1, 'B').unwrap());
+    queue.push(stop(1));
+    queue.push_with_priority(Message::go_to_preset(2, 1).unwrap(), Priority::Low);
+    queue.push(stop(2));
+    assert_eq!(6, queue.len());
+
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+    port.set_rate_limit(RateLimit {
+        min_gap: Duration::from_millis(50),
+        max_frames_per_second: 0,
+    });
+    assert_eq!(6, queue.flush(&mut port).unwrap());
+    assert!(queue.is_empty());
+    assert_eq!(Duration::from_millis(250), clock.now());
+
+    assert_eq!(
+        vec![
+            stop(1),
+            stop(2),
+            Message::go_to_preset(1, 1).unwrap(),
+            Message::write_char_to_screen(1, 0, 'A').unwrap(),
+            Message::write_char_to_screen(1, 1, 'B').unwrap(),
+            Message::go_to_preset(2, 1).unwrap(),
+        ],
+        messages(&device)
+    );
+}
+
+#[test]
+fn test_queue_stop_drops_motion() {
+    let mut queue = SendQueue::new();
+    queue.push(pan_left(1, 0.5));
+    queue.push(Message::go_to_preset(1, 1).unwrap());
+    queue.push(pan_left(2, 0.5));
+    queue.push(stop(1));
+    assert_eq!(3, queue.len());
+
+    assert_eq!(Some(stop(1)), queue.pop());
+    assert_eq!(Some(Message::go_to_preset(1, 1).unwrap()), queue.pop());
+    assert_eq!(Some(pan_left(2, 0.5)), queue.pop());
+    assert_eq!(None, queue.pop());
+}
+
+#[test]
+fn test_queue_send_error() {
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    let mut queue = SendQueue::new();
+    queue.push(Message::go_to_preset(1, 1).unwrap());
+    queue.push(stop(1));
+
+    device.inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    assert!(queue.send_next(&mut port).is_err());
+    assert_eq!(2, queue.len());
+
+    assert_eq!(Some(stop(1)), queue.send_next(&mut port).unwrap());
+    assert_eq!(
+        Some(Message::go_to_preset(1, 1).unwrap()),
+        queue.send_next(&mut port).unwrap()
+    );
+    assert_eq!(None, queue.send_next(&mut port).unwrap());
+
+    queue.push(stop(1));
+    queue.clear();
+    assert_eq!(None, queue.pop());
+}
+
+fn pan_left(address: u8, speed: f32) -> Message {
+    MessageBuilder::new(address)
+        .unwrap()
+        .left()
+        .pan(Speed::Range(speed))
+        .finalize()
+        .unwrap()
+}
+
+#[test]
+fn test_coalescing_rules() {
+    assert_eq!(Coalescing::Latest, Coalescing::of(&stop(1)));
+    assert_eq!(Coalescing::Latest, Coalescing::of(&pan_left(1, 0.5)));
+    let iris = MessageBuilder::new(1)
+        .unwrap()
+        .open_iris()
+        .finalize()
+        .unwrap();
+    assert_eq!(Coalescing::Latest, Coalescing::of(&iris));
+    let camera = MessageBuilder::new(1)
+        .unwrap()
+        .camera_on()
+        .finalize()
+        .unwrap();
+    assert_eq!(Coalescing::Ordered, Coalescing::of(&camera));
+    for message in [
+        Message::set_preset(1, 1).unwrap(),
+        Message::set_auxiliary(1, 0, 1).unwrap(),
+        Message::set_pattern_start(1, 1).unwrap(),
+    ]
+    .iter()
+    {
+        assert_eq!(Coalescing::Ordered, Coalescing::of(message));
+    }
+}
+
+#[test]
+fn test_coalescing_queue() {
+    let mut queue = CoalescingQueue::new();
+    for i in 1..=10 {
+        queue.push(pan_left(1, i as f32 / 10.0));
+        queue.push(pan_left(2, i as f32 / 10.0));
+    }
+    assert!(!queue.push(Message::set_pattern_start(1, 1).unwrap()));
+    assert!(!queue.push(pan_left(1, 0.2)));
+    assert!(queue.push(stop(1)));
+    assert!(!queue.push(Message::set_pattern_stop(1, 1).unwrap()));
+    assert!(queue.push(stop(2)));
+
+    assert_eq!(
+        vec![
+            pan_left(1, 1.0),
+            stop(2),
+            Message::set_pattern_start(1, 1).unwrap(),
+            stop(1),
+            Message::set_pattern_stop(1, 1).unwrap(),
+        ],
+        queue.messages()
+    );
+    assert_eq!(20, queue.coalesced());
+
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    assert_eq!(5, queue.flush(&mut port).unwrap());
+    assert!(queue.is_empty());
+    assert_eq!(5, device.sent().len());
+}
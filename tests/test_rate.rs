@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+fn new_port(clock: &ManualClock, limit: RateLimit) -> (PelcoDPort<MockTransport>, MockTransport) {
+    let (mut port, device) = timed_port(clock);
+    port.set_rate_limit(limit);
+    (port, device)
+}
+
+/// Reception times, in milliseconds, and addresses.
+fn reception_times(device: &MockTransport) -> Vec<(u128, u8)> {
+    received(device)
+        .iter()
+        .map(|(t, m)| (t.as_millis(), m.as_ref()[1]))
+        .collect()
+}
+
+#[test]
+fn test_rate_min_gap() {
+    let clock = ManualClock::new();
+    let (mut port, device) = new_port(
+        &clock,
+        RateLimit {
+            min_gap: Duration::from_millis(40),
+            ..RateLimit::default()
+        },
+    );
+
+    port.send_message(Message::go_to_preset(1, 1).unwrap())
+        .unwrap();
+    port.send_message(Message::go_to_preset(2, 1).unwrap())
+        .unwrap();
+    clock.advance(Duration::from_millis(100));
+    port.send_message(Message::go_to_preset(3, 1).unwrap())
+        .unwrap();
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(Duration::from_millis(30), port.send_delay(1));
+    port.send_message(Message::go_to_preset(1, 1).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        vec![(0, 1), (40, 2), (140, 3), (180, 1)],
+        reception_times(&device)
+    );
+}
+
+#[test]
+fn test_rate_max_frames_per_second() {
+    let clock = ManualClock::new();
+    let (mut port, device) = new_port(
+        &clock,
+        RateLimit {
+            min_gap: Duration::from_millis(10),
+            max_frames_per_second: 4,
+        },
+    );
+
+    for address in [1, 2, 1, 1, 2].iter() {
+        port.send_message(Message::go_to_preset(*address, 1).unwrap())
+            .unwrap();
+    }
+
+    assert_eq!(
+        vec![(0, 1), (10, 2), (250, 1), (500, 1), (510, 2)],
+        reception_times(&device)
+    );
+    assert_eq!(port.rate_limit().max_frames_per_second, 4);
+}
@@ -0,0 +1,80 @@
+use pelcodrs::*;
+
+#[test]
+fn test_parse_responses() {
+    let response = Response::parse(ResponseKind::General, &[0xFF, 3, 0x80, 0x83]).unwrap();
+    assert_eq!(
+        Response::General {
+            address: 3,
+            alarms: 0x80
+        },
+        response
+    );
+
+    let response = Response::parse(
+        ResponseKind::Extended,
+        &[0xFF, 3, 0x00, 0x59, 0x23, 0x28, 0xA7],
+    )
+    .unwrap();
+    assert_eq!(
+        Response::Extended {
+            address: 3,
+            words: [0x00, 0x59, 0x23, 0x28]
+        },
+        response
+    );
+
+    let mut bytes = vec![0xFF, 4];
+    bytes.extend_from_slice(b"DD53CBW        ");
+    bytes.push(checksum(&bytes[1..]));
+    let response = Response::parse(ResponseKind::Query, &bytes).unwrap();
+    assert_eq!(4, response.address());
+    assert_eq!(ResponseKind::Query, response.kind());
+    assert_eq!(Some(String::from("DD53CBW")), response.part_number());
+}
+
+#[test]
+fn test_parse_invalid_responses() {
+    let err = Response::parse(ResponseKind::General, &[0xFF, 3, 0x80, 0x84]).unwrap_err();
+    match err.kind() {
+        ErrorKind::ChecksumMismatch => (),
+        kind => panic!("Unexpected error kind {:?}", kind),
+    }
+    let _ = Response::parse(ResponseKind::General, &[0xFE, 3, 0x80, 0x83])
+        .expect_err("Invalid sync byte should fail");
+    let _ = Response::parse(ResponseKind::Extended, &[0xFF, 3, 0x80, 0x83])
+        .expect_err("Invalid size should fail");
+}
+
+#[test]
+fn test_response_parser_resync() {
+    let mut parser = ResponseParser::new(ResponseKind::General);
+    parser.push(&[0x12, 0x34, 0xFF, 1, 0x01, 0x05, 0xFF, 2, 0x00]);
+
+    let _ = parser
+        .next_response()
+        .unwrap()
+        .expect_err("Bad checksum should fail");
+    assert!(parser.next_response().is_none());
+
+    parser.push(&[0x02]);
+    assert_eq!(
+        Response::General {
+            address: 2,
+            alarms: 0
+        },
+        parser.next_response().unwrap().unwrap()
+    );
+    assert_eq!(0, parser.pending());
+
+    parser.set_kind(ResponseKind::Extended);
+    let msg = Message::from_bytes(9, [0, 0x59, 0, 0]);
+    parser.push(msg.as_ref());
+    assert_eq!(
+        Response::Extended {
+            address: 9,
+            words: [0, 0x59, 0, 0]
+        },
+        parser.next_response().unwrap().unwrap()
+    );
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+fn send(message: Message) -> ScriptStep {
+    ScriptStep::Send(message)
+}
+
+fn wait(ms: u64) -> ScriptStep {
+    ScriptStep::Wait(Duration::from_millis(ms))
+}
+
+fn error_line(text: &str) -> usize {
+    let err = text.parse::<Script>().expect_err("Script should fail");
+    match err.kind() {
+        ErrorKind::Script { line } => *line,
+        kind => panic!("Unexpected error kind {:?}", kind),
+    }
+}
+
+#[test]
+fn test_script_commands() {
+    let script: Script = "
+        # Site entrance
+        camera 10
+        goto 5
+        PRESET SET 6
+        preset clear 7
+        aux on 1
+        aux off 1
+        pattern run 2
+        flip
+        tilt up 0.5 for 1.5s
+        zoom out for 2s
+        focus far
+        stop
+        camera 11
+        send addr=11 CLEAR_SCREEN
+        send FF 0B 00 07 00 01 13
+        wait 1m
+    "
+    .parse()
+    .unwrap();
+
+    let stop10 = MessageBuilder::new(10).unwrap().stop().finalize().unwrap();
+    assert_eq!(
+        &[
+            send(Message::go_to_preset(10, 5).unwrap()),
+            send(Message::set_preset(10, 6).unwrap()),
+            send(Message::clear_preset(10, 7).unwrap()),
+            send(Message::set_auxiliary(10, 0, 1).unwrap()),
+            send(Message::clear_auxiliary(10, 0, 1).unwrap()),
+            send(Message::run_pattern(10, 2).unwrap()),
+            send(Message::flip_180(10).unwrap()),
+            send(
+                MessageBuilder::new(10)
+                    .unwrap()
+                    .up()
+                    .tilt(Speed::Range(0.5))
+                    .finalize()
+                    .unwrap()
+            ),
+            wait(1500),
+            send(stop10),
+            send(
+                MessageBuilder::new(10)
+                    .unwrap()
+                    .zoom_out()
+                    .finalize()
+                    .unwrap()
+            ),
+            wait(2000),
+            send(stop10),
+            send(
+                MessageBuilder::new(10)
+                    .unwrap()
+                    .focus_far()
+                    .finalize()
+                    .unwrap()
+            ),
+            send(stop10),
+            send(Message::clear_screen(11).unwrap()),
+            send(Message::go_to_preset(11, 1).unwrap()),
+            wait(60_000),
+        ],
+        script.steps()
+    );
+}
+
+#[test]
+fn test_script_nested_repeat() {
+    let script: Script = "camera 1\nrepeat 2 {\n  goto 1\n  repeat 3 {\n    wait 10ms\n  }\n}\n"
+        .parse()
+        .unwrap();
+    assert_eq!(8, script.steps().len());
+    assert_eq!(Duration::from_millis(60), script.duration());
+}
+
+#[test]
+fn test_script_errors() {
+    assert_eq!(1, error_line("goto 1"));
+    assert_eq!(2, error_line("camera 1\njump 3"));
+    assert_eq!(2, error_line("camera 1\ngoto 0"));
+    assert_eq!(3, error_line("camera 1\n\npan left 2.0"));
+    assert_eq!(2, error_line("camera 1\nrepeat 2 {\ngoto 1"));
+    assert_eq!(3, error_line("camera 1\nrepeat 2 {\nwait 3 s\n}"));
+    assert_eq!(2, error_line("camera 1\ngoto 1 for 2s"));
+    assert_eq!(2, error_line("camera 1\nwait 3s for 2s"));
+    assert_eq!(1, error_line("camera 1 for 2s"));
+    assert_eq!(2, error_line("camera 1\n}"));
+    assert_eq!(1, error_line("send addr=1 JUMP"));
+    assert_eq!(2, error_line("camera 1\nrepeat 1001 {\ngoto 1\n}"));
+    assert_eq!(
+        2,
+        error_line("camera 1\nrepeat 1000 {\nrepeat 1000 {\ngoto 1\n}\n}")
+    );
+}
+
+#[test]
+fn test_script_dry_run_and_run() {
+    let script: Script = "camera 10\ngoto 5\nwait 3s".parse().unwrap();
+    assert_eq!(
+        "send FF 0A 00 07 00 05 16  ; addr=10 GOTO_PRESET 5\nwait 3000ms\n",
+        script.dry_run()
+    );
+
+    let clock = ManualClock::new();
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    port.set_clock(clock.clone());
+    script.run(&mut port).unwrap();
+    assert_eq!(vec![Message::go_to_preset(10, 5).unwrap()], device.sent());
+    assert_eq!(Duration::from_secs(3), clock.now());
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "serde")]
+
+use pelcodrs::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+    let json = serde_json::to_string(&value).expect("Serialization failed");
+    let back: T = serde_json::from_str(&json).expect("Deserialization failed");
+    assert_eq!(value, back, "Round-trip failed through {}", json);
+}
+
+#[test]
+fn test_serde_message() {
+    let msg = Message::go_to_preset(10, 5).unwrap();
+    assert_eq!(
+        "\"FF 0A 00 07 00 05 16\"",
+        serde_json::to_string(&msg).unwrap()
+    );
+    round_trip(msg);
+    assert_eq!(
+        msg,
+        serde_json::from_str::<Message>("\"addr=10 GOTO_PRESET 5\"").unwrap()
+    );
+
+    let _ = serde_json::from_str::<Message>("\"FF 0A 00 07 00 05\"")
+        .expect_err("Too few bytes should fail");
+    let _ = serde_json::from_str::<Message>("[255,10,0,7,0,5,22]")
+        .expect_err("Byte arrays should fail");
+}
+
+#[test]
+fn test_serde_builder() {
+    let mut builder = MessageBuilder::new(10).unwrap();
+    builder.camera_on().left().pan(Speed::Range(0.5));
+    round_trip(builder);
+
+    let json = serde_json::to_string(&builder).unwrap();
+    assert!(json.starts_with("{\"address\":{\"Unit\":10},"), "{}", json);
+    let back: MessageBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(builder.finalize().unwrap(), back.finalize().unwrap());
+}
+
+#[test]
+fn test_serde_arguments() {
+    round_trip(Speed::Range(0.25));
+    round_trip(Speed::Turbo);
+    round_trip(ZoomSpeed::High);
+    round_trip(FocusSpeed::Slow);
+    round_trip(AutoCtrl::Off);
+    round_trip(OnOff::Value(3));
+    round_trip(ShutterSpeed::Value(1000));
+    round_trip(ShutterSpeed::Bytes(1, 2));
+    round_trip(ShutterSpeed::NTSC);
+    round_trip(AdjustmentValue::New(300));
+    round_trip(AdjustmentValue::Delta(-20));
+}
+
+#[test]
+fn test_serde_flags() {
+    let cmd1 = Command1::SENSE | Command1::CAMERA_ON_OFF;
+    assert_eq!("136", serde_json::to_string(&cmd1).unwrap());
+    round_trip(cmd1);
+    round_trip(Command2::ZOOM_TELE | Command2::LEFT);
+    round_trip(Direction::UP | Direction::RIGHT);
+
+    let _ = serde_json::from_str::<Direction>("1").expect_err("Invalid bits should fail");
+}
@@ -0,0 +1,90 @@
+#![cfg(all(feature = "serial", unix))]
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use pelcodrs::*;
+use serialport::{DataBits, Parity, SerialPort, StopBits, TTYPort};
+
+/// Pseudo-terminal: the device side, and the path of the port side.
+fn new_pty() -> (TTYPort, String) {
+    let (device, port) = TTYPort::pair().expect("Cannot create pseudo-terminal");
+    let path = port.name().unwrap();
+    (device, path)
+}
+
+#[test]
+fn test_serial_open() {
+    let (mut device, path) = new_pty();
+    let mut port = PelcoDPort::open(&path, Baud::B4800).unwrap();
+
+    // The settings of the pseudo-terminal are shared by both sides
+    assert_eq!(4800, device.baud_rate().unwrap());
+    assert_eq!(DataBits::Eight, device.data_bits().unwrap());
+    assert_eq!(Parity::None, device.parity().unwrap());
+    assert_eq!(StopBits::One, device.stop_bits().unwrap());
+
+    let message = Message::go_to_preset(10, 5).unwrap();
+    port.send_message(message).unwrap();
+    let mut buf = [0u8; 7];
+    device.read_exact(&mut buf).unwrap();
+    assert_eq!(message.as_ref(), &buf);
+
+    port.set_baud(Baud::B9600).unwrap();
+    assert_eq!(9600, device.baud_rate().unwrap());
+
+    // Exclusive access
+    assert!(PelcoDPort::open(&path, Baud::B4800).is_err());
+    drop(port);
+    assert!(PelcoDPort::open(&path, Baud::B4800).is_ok());
+}
+
+#[test]
+fn test_serial_open_detect() {
+    let (mut device, path) = new_pty();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Device at address 10 answering only at 4800 baud
+    let device_stop = stop.clone();
+    let device_thread = thread::spawn(move || {
+        let mut frame = Vec::new();
+        while !device_stop.load(Ordering::SeqCst) {
+            let mut buf = [0u8; 7];
+            match device.read(&mut buf) {
+                Ok(count) => frame.extend_from_slice(&buf[..count]),
+                Err(_) => continue,
+            }
+            if frame.len() < 7 {
+                continue;
+            }
+            let query = Message::from_bytes(10, [0x00, 0x45, 0x00, 0x00]);
+            if frame[..7] == *query.as_ref() && device.baud_rate().unwrap() == 4800 {
+                let mut response = vec![0xFF, 0x0A];
+                response.extend_from_slice(b"DD5-PTZ        ");
+                response.push(checksum(&response[1..]));
+                device.write_all(&response).unwrap();
+            }
+            frame.drain(..7);
+        }
+    });
+
+    let (mut port, baud) = PelcoDPort::open_detect(&path, 10).unwrap();
+    assert_eq!(Baud::B4800, baud);
+    let response = port
+        .transaction(
+            Message::from_bytes(10, [0x00, 0x45, 0x00, 0x00]),
+            ResponseKind::Query,
+        )
+        .unwrap();
+    assert_eq!(Some("DD5-PTZ".to_string()), response.part_number());
+
+    drop(port);
+
+    let error = PelcoDPort::open_detect(&path, 11).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Timeout));
+
+    stop.store(true, Ordering::SeqCst);
+    device_thread.join().unwrap();
+}
@@ -0,0 +1,152 @@
+use std::io::ErrorKind as IoErrorKind;
+use std::io::Write;
+use std::time::Duration;
+
+use pelcodrs::testing::{Failure, MockTransport};
+use pelcodrs::*;
+
+fn general(address: u8) -> Response {
+    Response::General { address, alarms: 0 }
+}
+
+#[test]
+fn test_mock_expectations() {
+    let clock = ManualClock::new();
+    let mock = MockTransport::new();
+    mock.set_clock(clock.clone());
+    mock.expect(Message::go_to_preset(1, 5).unwrap())
+        .reply(&[0xFF, 0x01, 0x00, 0x01])
+        .delay(Duration::from_millis(20));
+    mock.expect(Message::clear_screen(1).unwrap());
+
+    let mut port = PelcoDPort::new(mock.clone());
+    port.set_clock(clock.clone());
+    let response = port
+        .transaction(Message::go_to_preset(1, 5).unwrap(), ResponseKind::General)
+        .unwrap();
+    assert_eq!(general(1), response);
+    assert_eq!(Duration::from_millis(20), clock.now());
+    assert_eq!(1, mock.pending());
+
+    port.send_message(Message::clear_screen(1).unwrap())
+        .unwrap();
+    mock.verify();
+
+    port.send_message(Message::flip_180(1).unwrap()).unwrap();
+    assert_sent!(
+        mock,
+        Message::go_to_preset(1, 5).unwrap(),
+        Message::flip_180(1).unwrap(),
+    );
+    assert_not_sent!(mock, Message::go_to_preset(1, 6).unwrap());
+    assert_eq!(3, mock.sent().len());
+    assert_eq!(
+        (Duration::from_millis(20), Message::clear_screen(1).unwrap()),
+        mock.sent_at()[1]
+    );
+    assert_eq!(3, mock.writes());
+
+    mock.clear_sent();
+    assert!(mock.written().is_empty());
+
+    mock.unsolicited(&[0xFF, 0x01, 0x00, 0x01]);
+    assert_eq!(
+        Some(general(1)),
+        port.receive_response(ResponseKind::General).unwrap()
+    );
+}
+
+#[test]
+#[should_panic(expected = "unexpected message addr=1 GOTO_PRESET 6")]
+fn test_mock_unexpected_message() {
+    let mock = MockTransport::new();
+    mock.expect(Message::go_to_preset(1, 5).unwrap());
+    let mut port = PelcoDPort::new(mock.clone());
+    port.send_message(Message::go_to_preset(1, 6).unwrap())
+        .unwrap();
+    mock.verify();
+}
+
+#[test]
+#[should_panic(expected = "unexpected message addr=1 GOTO_PRESET 6")]
+fn test_mock_message_after_expectations() {
+    let mock = MockTransport::new();
+    mock.expect(Message::go_to_preset(1, 5).unwrap());
+    let mut port = PelcoDPort::new(mock.clone());
+    port.send_message(Message::go_to_preset(1, 5).unwrap())
+        .unwrap();
+    port.send_message(Message::go_to_preset(1, 6).unwrap())
+        .unwrap();
+    mock.verify();
+}
+
+#[test]
+#[should_panic(expected = "addr=1 GOTO_PRESET 5 was not sent")]
+fn test_assert_sent_missing() {
+    let mock = MockTransport::new();
+    let mut port = PelcoDPort::new(mock.clone());
+    port.send_message(Message::go_to_preset(1, 6).unwrap())
+        .unwrap();
+    assert_sent!(mock, Message::go_to_preset(1, 5).unwrap());
+}
+
+#[test]
+fn test_mock_failures() {
+    let clock = ManualClock::new();
+    let mock = MockTransport::new();
+    mock.set_clock(clock.clone());
+    let mut port = PelcoDPort::new(mock.clone());
+    port.set_clock(clock.clone());
+    let message = Message::go_to_preset(1, 5).unwrap();
+
+    // Short writes are completed by the port
+    mock.expect(message);
+    mock.inject(Failure::ShortWrite(3));
+    port.send_message(message).unwrap();
+    assert_eq!(vec![message], mock.sent());
+
+    // The frame of a failed write is dropped
+    mock.clear_sent();
+    mock.inject(Failure::ShortWrite(3))
+        .inject(Failure::WriteError(IoErrorKind::BrokenPipe));
+    let error = port.send_message(message).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(e) if e.kind() == IoErrorKind::BrokenPipe));
+    mock.expect(Message::clear_screen(1).unwrap());
+    port.send_message(Message::clear_screen(1).unwrap())
+        .unwrap();
+    assert_eq!(vec![Message::clear_screen(1).unwrap()], mock.sent());
+
+    // Bytes out of a frame are skipped up to the next sync byte
+    mock.clear_sent();
+    mock.clone().write_all(&[0x01, 0xFF, 0x01, 0x00]).unwrap();
+    mock.expect(message);
+    port.send_message(message).unwrap();
+    assert_eq!(vec![message], mock.sent());
+
+    mock.inject(Failure::ReadError(IoErrorKind::ConnectionReset));
+    assert!(port.receive_response(ResponseKind::General).is_err());
+
+    mock.inject(Failure::ReadDelay(Duration::from_millis(50)));
+    mock.unsolicited(&[0xFF, 0x01, 0x00, 0x01]);
+    assert_eq!(None, port.receive_response(ResponseKind::General).unwrap());
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(
+        Some(general(1)),
+        port.receive_response(ResponseKind::General).unwrap()
+    );
+
+    mock.inject(Failure::CorruptedRead);
+    mock.unsolicited(&[0xFF, 0x01, 0x00, 0x01]);
+    let error = port.receive_response(ResponseKind::General).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::ChecksumMismatch));
+
+    // Corrupted reply, then a good one on retry
+    mock.expect(message)
+        .reply(&[0xFF, 0x01, 0x00, 0x01])
+        .corrupted();
+    mock.expect(message).reply(&[0xFF, 0x01, 0x00, 0x01]);
+    let response = port.transaction(message, ResponseKind::General).unwrap();
+    assert_eq!(general(1), response);
+    assert_eq!(1, port.metrics().retries);
+    mock.verify();
+}
@@ -0,0 +1,153 @@
+use std::io::{Read, Write};
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+fn two_presets_tour() -> Tour {
+    Tour::new(
+        10,
+        vec![
+            TourStep::GoToPreset(1),
+            TourStep::Dwell(secs(5)),
+            TourStep::GoToPreset(2),
+            TourStep::Send(
+                MessageBuilder::new(10)
+                    .unwrap()
+                    .zoom_in()
+                    .finalize()
+                    .unwrap(),
+            ),
+            TourStep::Dwell(secs(10)),
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_invalid_tour() {
+    let _ = Tour::new(1, vec![]).expect_err("Empty tour should fail");
+    let _ = Tour::new(1, vec![TourStep::GoToPreset(0)]).expect_err("Preset 0 should fail");
+    let _ = Tour::new(1, vec![TourStep::GoToPreset(1), TourStep::GoToPreset(2)])
+        .expect_err("Tour without dwell should fail");
+    let _ = Tour::new(1, vec![TourStep::GoToPreset(1), TourStep::Dwell(secs(0))])
+        .expect_err("Tour with zero dwell should fail");
+}
+
+#[test]
+fn test_tour_tick() {
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    let mut tour = two_presets_tour();
+
+    let events = tour.tick(&mut port, secs(0)).unwrap();
+    assert_eq!(2, events.len());
+    assert_eq!(
+        vec![Message::go_to_preset(10, 1).unwrap()],
+        messages(&device)
+    );
+    assert_eq!(secs(5), tour.next_deadline());
+
+    assert!(tour.tick(&mut port, secs(4)).unwrap().is_empty());
+    assert!(messages(&device).is_empty());
+
+    let events = tour.tick(&mut port, secs(5)).unwrap();
+    assert_eq!(TourEvent::CycleCompleted(1), *events.last().unwrap());
+    assert_eq!(
+        vec![
+            Message::go_to_preset(10, 2).unwrap(),
+            MessageBuilder::new(10)
+                .unwrap()
+                .zoom_in()
+                .finalize()
+                .unwrap()
+        ],
+        messages(&device)
+    );
+    assert_eq!(secs(15), tour.next_deadline());
+
+    tour.tick(&mut port, secs(15)).unwrap();
+    assert_eq!(
+        vec![Message::go_to_preset(10, 1).unwrap()],
+        messages(&device)
+    );
+}
+
+#[test]
+fn test_tour_pause_and_resume() {
+    let device = MockTransport::new();
+    let mut port = PelcoDPort::new(device.clone());
+    let mut tour = two_presets_tour();
+    tour.set_idle_timeout(secs(20));
+
+    tour.tick(&mut port, secs(0)).unwrap();
+    tour.tick(&mut port, secs(5)).unwrap();
+    messages(&device);
+
+    assert_eq!(Some(TourEvent::Paused), tour.operator_activity(secs(6)));
+    assert_eq!(None, tour.operator_activity(secs(8)));
+    assert!(tour.is_paused());
+    assert_eq!(secs(28), tour.next_deadline());
+
+    assert!(tour.tick(&mut port, secs(27)).unwrap().is_empty());
+    assert!(messages(&device).is_empty());
+
+    let events = tour.tick(&mut port, secs(28)).unwrap();
+    assert_eq!(TourEvent::Resumed, events[0]);
+    assert_eq!(TourEvent::Step(2, TourStep::GoToPreset(2)), events[1]);
+    assert!(!tour.is_paused());
+    assert_eq!(Message::go_to_preset(10, 2).unwrap(), messages(&device)[0]);
+}
+
+#[test]
+fn test_tour_thread() {
+    let device = MockTransport::new();
+    let clock = ManualClock::new();
+    let mut port = PelcoDPort::new(device.clone());
+    port.set_clock(clock.clone());
+    let handle = two_presets_tour().spawn(port);
+
+    let events: Vec<TourEvent> = handle
+        .events()
+        .iter()
+        .take_while(|event| *event != TourEvent::CycleCompleted(2))
+        .collect();
+    handle.stop().expect("Tour should stop without error");
+
+    assert_eq!(11, events.len());
+    assert!(clock.now() >= secs(20));
+    let sent = messages(&device);
+    assert_eq!(Message::go_to_preset(10, 1).unwrap(), sent[0]);
+    assert_eq!(Message::go_to_preset(10, 2).unwrap(), sent[1]);
+    assert_eq!(Message::go_to_preset(10, 1).unwrap(), sent[3]);
+}
+
+struct PanickingDevice;
+
+impl Read for PanickingDevice {
+    fn read(&mut self, _buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        Ok(0)
+    }
+}
+
+impl Write for PanickingDevice {
+    fn write(&mut self, _buf: &[u8]) -> std::result::Result<usize, std::io::Error> {
+        panic!("device failure");
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+#[should_panic(expected = "device failure")]
+fn test_tour_thread_panic() {
+    let mut port = PelcoDPort::new(PanickingDevice);
+    port.set_clock(ManualClock::new());
+    let handle = two_presets_tour().spawn(port);
+    let _ = handle.events().recv();
+    let _ = handle.stop();
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt::Debug;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use pelcodrs::*;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Recorded event: level, span and fields.
+#[derive(Debug, Clone)]
+struct Recorded {
+    level: Level,
+    span: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Recorded {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .push((field.name().to_string(), format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_string(), value.to_string()));
+    }
+}
+
+/// Subscriber keeping all the events.
+#[derive(Clone, Default)]
+struct Collector {
+    events: Arc<Mutex<Vec<Recorded>>>,
+    spans: Arc<Mutex<Vec<&'static str>>>,
+    current: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata().name());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Vec::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        let span = self
+            .current
+            .lock()
+            .unwrap()
+            .last()
+            .map(|id| self.spans.lock().unwrap()[*id as usize - 1].to_string());
+        self.events.lock().unwrap().push(Recorded {
+            level: *event.metadata().level(),
+            span,
+            fields,
+        });
+    }
+
+    fn enter(&self, span: &Id) {
+        self.current.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.current.lock().unwrap().pop();
+    }
+}
+
+fn collect<F: FnOnce()>(f: F) -> Vec<Recorded> {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), f);
+    let events = collector.events.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn test_tracing_sent_frame() {
+    let events = collect(|| {
+        let mut port = PelcoDPort::new(Cursor::new(Vec::new()));
+        port.set_clock(ManualClock::new());
+        port.send_message(Message::go_to_preset(10, 5).unwrap())
+            .unwrap();
+    });
+
+    let trace = events
+        .iter()
+        .find(|e| e.field("message") == Some("bytes written"))
+        .unwrap();
+    assert_eq!(Level::TRACE, trace.level);
+    assert_eq!(Some("FF 0A 00 07 00 05 16"), trace.field("bytes"));
+
+    let sent = events
+        .iter()
+        .find(|e| e.field("message") == Some("frame sent"))
+        .unwrap();
+    assert_eq!(Level::DEBUG, sent.level);
+    assert_eq!(Some("10"), sent.field("address"));
+    assert_eq!(Some("addr=10 GOTO_PRESET 5"), sent.field("command"));
+    assert_eq!(Some("FF 0A 00 07 00 05 16"), sent.field("bytes"));
+    assert_eq!(Some("0"), sent.field("elapsed_us"));
+}
+
+#[test]
+fn test_tracing_received_response() {
+    let events = collect(|| {
+        let bytes = vec![0xFF, 0x0A, 0x01, 0x0B, 0xFF, 0x0A, 0x01, 0x00];
+        let mut port = PelcoDPort::new(Cursor::new(bytes));
+        port.receive_response(ResponseKind::General).unwrap();
+        assert!(port.receive_response(ResponseKind::General).is_err());
+    });
+
+    let received = events
+        .iter()
+        .find(|e| e.field("message") == Some("response received"))
+        .unwrap();
+    assert_eq!(Level::DEBUG, received.level);
+    assert_eq!(Some("receive_response"), received.span.as_deref());
+    assert_eq!(Some("10"), received.field("address"));
+
+    let invalid = events
+        .iter()
+        .find(|e| e.field("message") == Some("invalid response"))
+        .unwrap();
+    assert_eq!(Level::WARN, invalid.level);
+    assert!(invalid.field("error").is_some());
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::testing::MockTransport;
+use pelcodrs::*;
+
+const REPLY_10: [u8; 4] = [0xFF, 0x0A, 0x00, 0x0A];
+const REPLY_11: [u8; 4] = [0xFF, 0x0B, 0x00, 0x0B];
+const CORRUPTED_10: [u8; 4] = [0xFF, 0x0A, 0x00, 0x00];
+
+/// Port on a device answering each frame of `message` with the next reply,
+/// or with nothing for `None`.
+fn new_port(
+    clock: &ManualClock,
+    message: Message,
+    replies: &[Option<&[u8]>],
+) -> (PelcoDPort<MockTransport>, MockTransport) {
+    let (mut port, device) = timed_port(clock);
+    for reply in replies.iter() {
+        let expectation = device.expect(message);
+        if let Some(reply) = reply {
+            expectation.reply(reply);
+        }
+    }
+    port.set_retry_policy(RetryPolicy {
+        timeout: Duration::from_millis(100),
+        retries: 2,
+        backoff: Duration::from_millis(50),
+    });
+    (port, device)
+}
+
+#[test]
+fn test_transaction_immediate_reply() {
+    let clock = ManualClock::new();
+    let message = Message::go_to_preset(10, 5).unwrap();
+    let (mut port, device) = new_port(&clock, message, &[Some(&REPLY_10)]);
+
+    let response = port.transaction(message, ResponseKind::General).unwrap();
+    assert_eq!(
+        Response::General {
+            address: 10,
+            alarms: 0
+        },
+        response
+    );
+    assert_eq!(vec![message], device.sent());
+    assert_eq!(Duration::from_secs(0), clock.now());
+}
+
+#[test]
+fn test_transaction_retry_after_timeout() {
+    let clock = ManualClock::new();
+    let message = Message::go_to_preset(10, 5).unwrap();
+    let (mut port, device) = new_port(&clock, message, &[None, None, Some(&REPLY_10)]);
+
+    port.transaction(message, ResponseKind::General).unwrap();
+    assert_eq!(3, device.sent().len());
+    // Two timeouts, then backoffs of 50 and 100 ms
+    assert_eq!(Duration::from_millis(350), clock.now());
+
+    let metrics = port.metrics();
+    assert_eq!(2, metrics.timeouts);
+    assert_eq!(2, metrics.retries);
+    assert_eq!(2, metrics.addresses[&10].retries);
+    assert_eq!(1, metrics.addresses[&10].replies_received);
+}
+
+#[test]
+fn test_transaction_errors() {
+    let message = Message::go_to_preset(10, 5).unwrap();
+
+    let clock = ManualClock::new();
+    let (mut port, device) = new_port(&clock, message, &[]);
+    let error = port
+        .transaction(message, ResponseKind::General)
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Timeout));
+    assert_eq!(3, device.sent().len());
+
+    let (mut port, _) = new_port(
+        &clock,
+        message,
+        &[
+            Some(&CORRUPTED_10),
+            Some(&CORRUPTED_10),
+            Some(&CORRUPTED_10),
+        ],
+    );
+    let error = port
+        .transaction(message, ResponseKind::General)
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::ChecksumMismatch));
+    assert_eq!(3, port.metrics().checksum_failures);
+
+    let (mut port, _) = new_port(
+        &clock,
+        message,
+        &[Some(&REPLY_11), Some(&REPLY_11), Some(&REPLY_11)],
+    );
+    let error = port
+        .transaction(message, ResponseKind::General)
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedResponse));
+}
+
+#[test]
+fn test_transaction_call_policy() {
+    let clock = ManualClock::new();
+    let message = Message::go_to_preset(10, 5).unwrap();
+    let (mut port, device) = new_port(&clock, message, &[Some(&REPLY_11), Some(&REPLY_10)]);
+
+    let policy = RetryPolicy::no_retry(Duration::from_millis(10));
+    let error = port
+        .transaction_with(message, ResponseKind::General, &policy)
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedResponse));
+    assert_eq!(1, device.sent().len());
+
+    let response = port
+        .transaction_with(message, ResponseKind::General, &policy)
+        .unwrap();
+    assert_eq!(10, response.address());
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+mod common;
+
+use common::*;
+use pelcodrs::*;
+
+#[test]
+fn test_zone_plan() {
+    let mut zone = Zone::new(2, ZoneEdge::PanPosition(1000), ZoneEdge::Preset(4));
+    zone.set_label("AB").set_scan(false);
+
+    assert_eq!(
+        vec![
+            Message::set_pan_position(8, 1000).unwrap(),
+            Message::set_zone_start(8, 2).unwrap(),
+            Message::go_to_preset(8, 4).unwrap(),
+            Message::set_zone_end(8, 2).unwrap(),
+            Message::write_char_to_screen(8, 0, 'A').unwrap(),
+            Message::write_char_to_screen(8, 1, 'B').unwrap(),
+            Message::zone_scan_off(8).unwrap(),
+        ],
+        zone.plan(8).unwrap()
+    );
+}
+
+#[test]
+fn test_invalid_zone() {
+    let zone = Zone::new(2, ZoneEdge::Preset(0), ZoneEdge::Preset(4));
+    let _ = zone.plan(8).expect_err("Preset 0 should fail");
+
+    let mut zone = Zone::new(2, ZoneEdge::Preset(1), ZoneEdge::PanPosition(40000));
+    let _ = zone.plan(8).expect_err("Invalid pan position should fail");
+
+    zone = Zone::new(2, ZoneEdge::Preset(1), ZoneEdge::Preset(2));
+    zone.set_label("Zoné");
+    let _ = zone.plan(8).expect_err("Non-ASCII label should fail");
+}
+
+#[test]
+fn test_zone_apply() {
+    let clock = ManualClock::new();
+    let (mut port, device) = timed_port(&clock);
+
+    let mut zone = Zone::new(1, ZoneEdge::Preset(1), ZoneEdge::Preset(2));
+    zone.set_settle_time(Duration::from_secs(3)).set_scan(true);
+    zone.apply(&mut port, 6).unwrap();
+
+    assert_eq!(
+        vec![
+            (secs(0), Message::go_to_preset(6, 1).unwrap()),
+            (secs(3), Message::set_zone_start(6, 1).unwrap()),
+            (secs(3), Message::go_to_preset(6, 2).unwrap()),
+            (secs(6), Message::set_zone_end(6, 1).unwrap()),
+            (secs(6), Message::zone_scan_on(6).unwrap()),
+        ],
+        received(&device)
+    );
+}